@@ -1,15 +1,12 @@
 extern crate pty;
-extern crate libc;
 
 use pty::fork::*;
-use std::ffi::CString;
 use std::io::Read;
-use std::ptr;
 
 fn main() {
     let fork = Fork::from_ptmx().unwrap();
 
-    if let Some(mut master) = fork.is_parent().ok() {
+    if let Ok(mut master) = fork.is_parent() {
         // Read output via PTY master
         let mut output = String::new();
 
@@ -19,9 +16,6 @@ fn main() {
         }
     } else {
         // Child process just exec `tty`
-        let cmd = CString::new("tty").unwrap().as_ptr();
-        let args = [cmd, ptr::null()].as_mut_ptr();
-
-        unsafe { libc::execvp(cmd, args) };
+        exec("tty", &[]);
     }
 }
@@ -2,7 +2,7 @@ mod err;
 
 use ::libc;
 
-pub use self::err::DescriptorError;
+pub use self::err::{DescriptorError, Result};
 use std::os::unix::io::{AsRawFd, RawFd};
 
 pub trait Descriptor: AsRawFd {
@@ -11,7 +11,7 @@ pub trait Descriptor: AsRawFd {
     fn open(path: *const libc::c_char,
             flag: libc::c_int,
             mode: Option<libc::c_int>)
-            -> Result<RawFd, DescriptorError> {
+            -> Result<RawFd> {
         unsafe {
             match libc::open(path, flag, mode.unwrap_or_default()) {
                 -1 => Err(DescriptorError::OpenFail),
@@ -21,7 +21,7 @@ pub trait Descriptor: AsRawFd {
     }
 
     /// The function `close` leaves the fd.
-    fn close(&self) -> Result<(), DescriptorError> {
+    fn close(&self) -> Result<()> {
         unsafe {
             match libc::close(self.as_raw_fd()) {
                 -1 => Err(DescriptorError::CloseFail),
@@ -7,4 +7,6 @@ extern "C" {
     pub fn grantpt(fd: libc::c_int) -> libc::c_int;
     pub fn unlockpt(fd: libc::c_int) -> libc::c_int;
     pub fn ptsname(fd: libc::c_int) -> *const libc::c_schar;
+    #[cfg(any(target_os = "linux", target_os = "android", target_os = "freebsd"))]
+    pub fn ptsname_r(fd: libc::c_int, buf: *mut libc::c_char, buflen: libc::size_t) -> libc::c_int;
 }
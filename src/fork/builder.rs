@@ -0,0 +1,625 @@
+use ::libc;
+
+use super::{cloexec_pipe, reap, Detached, Fork, ForkError, Master, Result, Slave, StdStreams};
+use std::env;
+use std::ffi::{CStr, CString, OsStr};
+use std::io;
+use std::mem;
+use std::os::unix::ffi::{OsStrExt, OsStringExt};
+use std::os::unix::io::RawFd;
+use std::path::{Path, PathBuf};
+use std::ptr;
+
+/// The number of signals, `NSIG` of Linux which covers the other systems.
+const SIGNALS: libc::c_int = 65;
+
+/// The login shells which ignore the dash before their name.
+const LOGIN_FLAG_SHELLS: &[&str] = &["nu", "xonsh"];
+
+/// The steps of the child reported to the parent when they fail.
+const SETGROUPS: u8 = 1;
+const SETGID: u8 = 2;
+const SETUID: u8 = 3;
+const EXEC: u8 = 4;
+const CHDIR: u8 = 5;
+const INHERIT_FD: u8 = 6;
+const WINDOW_SIZE: u8 = 7;
+const FORK: u8 = 8;
+const SETSID: u8 = 9;
+const ATTACH: u8 = 10;
+
+/// The report of the intermediate child of `spawn_detached`, with the
+/// pid of the grandchild in place of the `errno`.
+const FORKED: u8 = 11;
+
+/// The length of a failure report, the step and its `errno`.
+const FAILURE_LEN: usize = 5;
+
+/// The default search path of `execvp` when `PATH` isn't set.
+const DEFAULT_PATH: &[u8] = b"/bin:/usr/bin";
+
+/// The struct `Prepared` holds the command and the configuration
+/// converted for the child before forking, so it doesn't allocate.
+struct Prepared {
+    // The paths the command is looked up at, in the order of `PATH`.
+    paths: Vec<CString>,
+    // Owns the arguments `ptrs` points to.
+    _argv: Vec<CString>,
+    ptrs: Vec<*const libc::c_char>,
+    // Owns the `KEY=VALUE` variables `envp` points to.
+    _env: Vec<CString>,
+    envp: Vec<*const libc::c_char>,
+    cwd: Option<CString>,
+}
+
+/// The struct `ForkBuilder` configures the child before its command
+/// is executed, so the program is set up from the start.
+#[derive(Clone, Debug, Default)]
+pub struct ForkBuilder {
+    arg0: Option<String>,
+    env: Vec<(String, String)>,
+    env_clear: bool,
+    window_size: Option<(u16, u16)>,
+    cwd: Option<PathBuf>,
+    hangup_on_drop: bool,
+    reset_signals: bool,
+    no_controlling_terminal: bool,
+    streams: StdStreams,
+    fds: Vec<(RawFd, RawFd)>,
+    uid: Option<libc::uid_t>,
+    gid: Option<libc::gid_t>,
+    groups: Option<Vec<libc::gid_t>>,
+}
+
+impl ForkBuilder {
+    /// The constructor function `new` returns a builder without configuration.
+    pub fn new() -> Self {
+        ForkBuilder::default()
+    }
+
+    /// Sets the first argument of the child, its name shown by `ps`,
+    /// instead of the command, like `-bash` for a login shell.
+    pub fn arg0(&mut self, name: &str) -> &mut Self {
+        self.arg0 = Some(name.to_string());
+        self
+    }
+
+    /// Sets the environment variable `key` to `value` in the child.
+    pub fn env(&mut self, key: &str, value: &str) -> &mut Self {
+        self.env.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// Clears the environment inherited by the child, so it has only the
+    /// variables set with `env`. The parent's environment is unchanged.
+    pub fn env_clear(&mut self) -> &mut Self {
+        self.env_clear = true;
+        self
+    }
+
+    /// Sets the terminal type `TERM` of the child, like `xterm-256color`.
+    pub fn term(&mut self, term: &str) -> &mut Self {
+        self.env("TERM", term)
+    }
+
+    /// Sets the window size of the terminal before the command is executed.
+    pub fn window_size(&mut self, rows: u16, cols: u16) -> &mut Self {
+        self.window_size = Some((rows, cols));
+        self
+    }
+
+    /// Sets the working directory of the child, the parent's one is unchanged.
+    pub fn cwd<P: AsRef<Path>>(&mut self, dir: P) -> &mut Self {
+        self.cwd = Some(dir.as_ref().to_path_buf());
+        self
+    }
+
+    /// Selects which standard streams of the child are the slave,
+    /// all of them by default.
+    pub fn std_streams(&mut self, streams: StdStreams) -> &mut Self {
+        self.streams = streams;
+        self
+    }
+
+    /// Duplicates the fd `source` onto `child_fd` in the child, without
+    /// close-on-exec, to pass a side channel like a socket besides the
+    /// terminal. The fds are duplicated in order after the standard
+    /// streams, so a `source` among 0, 1 and 2 is already the slave. The
+    /// spawn fails with `ForkError::InheritFdFail` if it can't be duplicated.
+    pub fn inherit_fd(&mut self, child_fd: RawFd, source: RawFd) -> &mut Self {
+        self.fds.push((child_fd, source));
+        self
+    }
+
+    /// Sets the user id of the child, after its groups. Unless `groups`
+    /// is set, the supplementary groups of root are dropped too.
+    pub fn user(&mut self, uid: libc::uid_t) -> &mut Self {
+        self.uid = Some(uid);
+        self
+    }
+
+    /// Sets the group id of the child, after its supplementary groups
+    /// but before its user, which couldn't change it anymore.
+    pub fn group(&mut self, gid: libc::gid_t) -> &mut Self {
+        self.gid = Some(gid);
+        self
+    }
+
+    /// Sets the supplementary groups of the child, first of its ids.
+    pub fn groups(&mut self, groups: &[libc::gid_t]) -> &mut Self {
+        self.groups = Some(groups.to_vec());
+        self
+    }
+
+    /// Opens the slave for the standard streams of the child with `O_NOCTTY`
+    /// and doesn't acquire it as its controlling terminal, so a sandboxed
+    /// child can't reach a terminal with `/dev/tty` or inject input with
+    /// `TIOCSTI`, which needs the controlling terminal without privileges.
+    ///
+    /// It's without job control: a shell warns that it has none, and the
+    /// `VINTR`, `VSUSP` and `VQUIT` characters or a hangup of the master
+    /// don't send a signal to the child, which is only stopped with `kill`.
+    pub fn no_controlling_terminal(&mut self) -> &mut Self {
+        self.no_controlling_terminal = true;
+        self
+    }
+
+    /// Sends `SIGHUP` to the process group of the child when the fork is
+    /// dropped, so a child which ignores the closed terminal is cleaned up.
+    /// It's off by default.
+    pub fn hangup_on_drop(&mut self, hangup: bool) -> &mut Self {
+        self.hangup_on_drop = hangup;
+        self
+    }
+
+    /// Resets the signal dispositions to the default and unblocks all
+    /// the signals in the child, like a shell does, since the child
+    /// otherwise inherits them, like the `SIGPIPE` ignored by Rust.
+    /// It's off by default.
+    pub fn reset_signals(&mut self, reset: bool) -> &mut Self {
+        self.reset_signals = reset;
+        self
+    }
+
+    /// Forks the program for a default PTMX's path and executes `cmd`
+    /// with the arguments `args` in the configured child, so only the
+    /// parent returns.
+    ///
+    /// The child reports through a close-on-exec pipe when the command
+    /// can't be executed, or its groups, user or working directory can't
+    /// be set, it's then reaped and the spawn fails with
+    /// `ForkError::ExecFail`, `ForkError::SetgroupsFail`,
+    /// `ForkError::SetgidFail`, `ForkError::SetuidFail`,
+    /// `ForkError::ChdirFail`, `ForkError::InheritFdFail` or
+    /// `ForkError::WindowSizeFail`. The child exits with the status 127
+    /// if it can't be set up otherwise.
+    pub fn spawn(&self, cmd: &str, args: &[&str]) -> Result<Fork> {
+        let prepared = self.prepare(cmd, args)?;
+        let (reader, writer) = cloexec_pipe().map_err(ForkError::Failure)?;
+        let parent = unsafe { libc::getpid() };
+
+        match Fork::fork_pty(::default_ptmx(), self.streams, !self.no_controlling_terminal) {
+            Ok(Fork::Parent(pid, mut master)) => {
+                unsafe { libc::close(writer) };
+
+                let failure = read_failure(reader);
+
+                unsafe { libc::close(reader) };
+                if let Some(err) = failure {
+                    reap(pid);
+                    return Err(err);
+                }
+                if self.hangup_on_drop {
+                    master.hangup_on_drop(pid);
+                }
+                Ok(Fork::Parent(pid, master))
+            }
+            Err(cause) => {
+                if unsafe { libc::getpid() } == parent {
+                    unsafe {
+                        libc::close(reader);
+                        libc::close(writer);
+                    }
+                    Err(cause)
+                } else {
+                    unsafe { libc::_exit(127) }
+                }
+            }
+            Ok(Fork::Child(ref slave)) => unsafe {
+                libc::close(reader);
+                self.exec_child(slave, &prepared, Some(writer))
+            },
+        }
+    }
+
+    /// Spawns the shell `path` as a login shell, which reads the profile
+    /// scripts, like `spawn` with the first argument set to the name of
+    /// the shell after a dash, `-bash` for `/bin/bash`.
+    ///
+    /// The dash is the convention of `login(1)`, honored by `sh`, `bash`,
+    /// `dash`, `zsh`, `ksh`, `mksh`, `csh`, `tcsh` and `fish`. The shells
+    /// which only take the `-l` flag, `nu` and `xonsh`, are also passed it.
+    pub fn login_shell(&self, path: &str) -> Result<Fork> {
+        let name = Path::new(path).file_name().and_then(|name| name.to_str()).unwrap_or(path);
+        let args: &[&str] = if LOGIN_FLAG_SHELLS.contains(&name) { &["-l"] } else { &[] };
+        let mut builder = self.clone();
+
+        builder.arg0(&format!("-{}", name)).spawn(path, args)
+    }
+
+    /// Spawns the command like `spawn` in a grandchild, the intermediate
+    /// child exits once it has forked, so the session isn't a child of the
+    /// caller and outlives it. The terminal lasts while a handle to the
+    /// master is open, like one passed to a server over a Unix socket.
+    ///
+    /// The intermediate child and the grandchild report their failures
+    /// like `spawn`, the spawn also fails with `ForkError::Failure` when the
+    /// grandchild can't be forked, `ForkError::SetsidFail` and
+    /// `ForkError::AttachFail` when it can't be set up in its session.
+    pub fn spawn_detached(&self, cmd: &str, args: &[&str]) -> Result<Detached> {
+        let prepared = self.prepare(cmd, args)?;
+        let mut master = Master::new(CString::new(::default_ptmx()).unwrap_or_default().as_ptr())
+            .map_err(ForkError::BadMaster)?;
+
+        master.grantpt().and(master.unlockpt()).map_err(ForkError::BadMaster)?;
+
+        let name = master.ptsname().map_err(ForkError::BadMaster)?;
+        let name = unsafe { CStr::from_ptr(name) }.to_owned();
+        let (reader, writer) = cloexec_pipe().map_err(ForkError::Failure)?;
+
+        match unsafe { libc::fork() } {
+            -1 => {
+                let err = io::Error::last_os_error();
+
+                unsafe {
+                    libc::close(reader);
+                    libc::close(writer);
+                }
+                Err(ForkError::Failure(err))
+            }
+            0 => unsafe {
+                libc::close(reader);
+                match libc::fork() {
+                    -1 => write_failure(writer, (FORK, ::errno::errno().0)),
+                    0 => {
+                        match Fork::from_pts(name, self.streams, !self.no_controlling_terminal) {
+                            Ok(Fork::Child(ref slave)) => {
+                                self.exec_child(slave, &prepared, Some(writer))
+                            }
+                            Err(ForkError::SetsidFail(err)) => {
+                                write_failure(writer, (SETSID, err.raw_os_error().unwrap_or(0)))
+                            }
+                            _ => write_failure(writer, (ATTACH, ::errno::errno().0)),
+                        }
+                    }
+                    pid => {
+                        write_failure(writer, (FORKED, pid));
+                        libc::_exit(0)
+                    }
+                }
+                libc::_exit(127)
+            },
+            intermediate => {
+                unsafe { libc::close(writer) };
+
+                // The grandchild can report before the intermediate child,
+                // the pipe is closed once both are done.
+                let mut pid = None;
+                let mut failure = None;
+
+                while let Some((step, value)) = read_report(reader) {
+                    match step {
+                        FORKED => pid = Some(value),
+                        step => failure = Some(failure_error(step, value)),
+                    }
+                }
+                unsafe { libc::close(reader) };
+                reap(intermediate);
+                if let Some(err) = failure {
+                    return Err(err);
+                }
+
+                let pid = match pid {
+                    Some(pid) => pid,
+                    None => {
+                        let err = io::Error::other("the intermediate child exited unreported");
+
+                        return Err(ForkError::Failure(err));
+                    }
+                };
+
+                if self.hangup_on_drop {
+                    master.hangup_on_drop(pid);
+                }
+                Ok(Detached::new(pid, master))
+            }
+        }
+    }
+
+    /// The function `prepare` converts the command `cmd`, its arguments
+    /// `args` and the configuration for the child before forking.
+    fn prepare(&self, cmd: &str, args: &[&str]) -> Result<Prepared> {
+        let cwd = match self.cwd {
+            Some(ref dir) => {
+                let dir = CString::new(dir.as_os_str().as_bytes());
+
+                Some(dir.map_err(|_| ForkError::InvalidString)?)
+            }
+            None => None,
+        };
+        let mut argv = match self.arg0 {
+            Some(ref name) => vec![cstring(name)?],
+            None => vec![cstring(cmd)?],
+        };
+        let mut vars: Vec<(Vec<u8>, Vec<u8>)> = if self.env_clear {
+            Vec::new()
+        } else {
+            env::vars_os().map(|(key, value)| (key.into_vec(), value.into_vec())).collect()
+        };
+
+        for arg in args {
+            argv.push(cstring(arg)?);
+        }
+        for (key, value) in &self.env {
+            cstring(key)?;
+            cstring(value)?;
+            vars.retain(|(name, _)| name.as_slice() != key.as_bytes());
+            vars.push((key.as_bytes().to_vec(), value.as_bytes().to_vec()));
+        }
+
+        // The command is looked up with the `PATH` of the child, as `execvp`
+        // would after setting its environment.
+        let search = vars.iter()
+                         .find(|&(name, _)| name.as_slice() == b"PATH")
+                         .map_or(DEFAULT_PATH, |(_, value)| value.as_slice());
+        let paths = command_paths(cmd, search)?;
+        let env = vars.into_iter()
+                      .map(|(mut key, value)| {
+                          key.push(b'=');
+                          key.extend_from_slice(&value);
+                          CString::new(key).map_err(|_| ForkError::InvalidString)
+                      })
+                      .collect::<Result<Vec<CString>>>()?;
+
+        let mut ptrs: Vec<*const libc::c_char> = argv.iter().map(|arg| arg.as_ptr()).collect();
+        let mut envp: Vec<*const libc::c_char> = env.iter().map(|var| var.as_ptr()).collect();
+
+        ptrs.push(ptr::null());
+        envp.push(ptr::null());
+
+        Ok(Prepared {
+            paths,
+            _argv: argv,
+            ptrs,
+            _env: env,
+            envp,
+            cwd,
+        })
+    }
+
+    /// The function `exec_child` sets up the child on its `slave` and
+    /// executes the `prepared` command, it reports the failure to set
+    /// the groups or the user or to execute the command to `report`
+    /// and exits otherwise.
+    unsafe fn exec_child(&self, slave: &Slave, prepared: &Prepared, report: Option<RawFd>) -> ! {
+        if let Some((rows, cols)) = self.window_size {
+            if let Err(err) = slave.set_window_size(rows, cols) {
+                if let Some(writer) = report {
+                    write_failure(writer, (WINDOW_SIZE, err.raw_os_error().unwrap_or(0)));
+                }
+                libc::_exit(127);
+            }
+        }
+        // The report pipe is moved above the inherited fds, which would
+        // replace it, while the sources were open before it. The slave may
+        // be replaced, it's already the standard streams.
+        let report = match report {
+            Some(writer) if !self.fds.is_empty() => {
+                let highest = self.fds.iter().map(|&(child_fd, _)| child_fd).max().unwrap_or(0);
+
+                match libc::fcntl(writer, libc::F_DUPFD_CLOEXEC, highest.saturating_add(1)) {
+                    -1 => {
+                        write_failure(writer, (INHERIT_FD, ::errno::errno().0));
+                        libc::_exit(127)
+                    }
+                    moved => Some(moved),
+                }
+            }
+            report => report,
+        };
+
+        for &(child_fd, source) in &self.fds {
+            if inherit_fd(child_fd, source) == -1 {
+                if let Some(writer) = report {
+                    write_failure(writer, (INHERIT_FD, ::errno::errno().0));
+                }
+                libc::_exit(127);
+            }
+        }
+        if self.reset_signals {
+            reset_signals();
+        }
+        if let Some(failure) = self.set_credentials() {
+            if let Some(writer) = report {
+                write_failure(writer, failure);
+            }
+            libc::_exit(127);
+        }
+        if let Some(ref dir) = prepared.cwd {
+            if libc::chdir(dir.as_ptr()) == -1 {
+                if let Some(writer) = report {
+                    write_failure(writer, (CHDIR, ::errno::errno().0));
+                }
+                libc::_exit(127);
+            }
+        }
+
+        // Like `execvp`, a path which can't be executed doesn't stop the
+        // lookup and its `EACCES` is reported if no other path works.
+        let mut failure = libc::ENOENT;
+
+        for path in &prepared.paths {
+            libc::execve(path.as_ptr(), prepared.ptrs.as_ptr(), prepared.envp.as_ptr());
+            match ::errno::errno().0 {
+                libc::ENOENT | libc::ENOTDIR => {}
+                libc::EACCES => failure = libc::EACCES,
+                errno => {
+                    failure = errno;
+                    break;
+                }
+            }
+        }
+        if let Some(writer) = report {
+            write_failure(writer, (EXEC, failure));
+        }
+        libc::_exit(127)
+    }
+}
+
+impl ForkBuilder {
+    /// The function `set_credentials` sets the supplementary groups, the
+    /// group and then the user of the child, since dropping the user first
+    /// would forbid to change the groups, and returns the failed step with
+    /// its `errno`.
+    unsafe fn set_credentials(&self) -> Option<(u8, libc::c_int)> {
+        let failure = |step| Some((step, ::errno::errno().0));
+
+        if let Some(ref groups) = self.groups {
+            if setgroups(groups) == -1 {
+                return failure(SETGROUPS);
+            }
+        } else if self.uid.is_some() && libc::getuid() == 0 {
+            // Otherwise the child keeps the groups of root.
+            if setgroups(&[]) == -1 {
+                return failure(SETGROUPS);
+            }
+        }
+        if let Some(gid) = self.gid {
+            if libc::setgid(gid) == -1 {
+                return failure(SETGID);
+            }
+        }
+        if let Some(uid) = self.uid {
+            if libc::setuid(uid) == -1 {
+                return failure(SETUID);
+            }
+        }
+        None
+    }
+}
+
+/// The function `cstring` converts `s` for the child, failing on a nul byte.
+fn cstring(s: &str) -> Result<CString> {
+    CString::new(s).map_err(|_| ForkError::InvalidString)
+}
+
+/// The function `command_paths` returns the paths `cmd` is looked up at,
+/// itself if it has a slash, or else in the `:` separated directories of
+/// `search`, where an empty one is the working directory.
+fn command_paths(cmd: &str, search: &[u8]) -> Result<Vec<CString>> {
+    if cmd.contains('/') {
+        return Ok(vec![cstring(cmd)?]);
+    }
+    if cmd.is_empty() {
+        return Ok(Vec::new());
+    }
+    search.split(|&byte| byte == b':')
+          .map(|dir| {
+              let dir = if dir.is_empty() { &b"."[..] } else { dir };
+
+              CString::new(Path::new(OsStr::from_bytes(dir)).join(cmd).into_os_string().into_vec())
+                  .map_err(|_| ForkError::InvalidString)
+          })
+          .collect()
+}
+
+/// The function `setgroups` sets the supplementary groups to `groups`.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+unsafe fn setgroups(groups: &[libc::gid_t]) -> libc::c_int {
+    libc::setgroups(groups.len(), groups.as_ptr())
+}
+
+/// The function `setgroups` sets the supplementary groups to `groups`,
+/// their count is an `int` outside of Linux.
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+unsafe fn setgroups(groups: &[libc::gid_t]) -> libc::c_int {
+    libc::setgroups(groups.len() as libc::c_int, groups.as_ptr())
+}
+
+/// The function `write_failure` reports the failed `step` and its
+/// `errno` from the child to the parent.
+unsafe fn write_failure(fd: RawFd, (step, errno): (u8, libc::c_int)) {
+    let mut report = [step; FAILURE_LEN];
+
+    report[1..].copy_from_slice(&errno.to_ne_bytes());
+    libc::write(fd, report.as_ptr() as *const libc::c_void, FAILURE_LEN);
+}
+
+/// The function `read_failure` returns the error for the failure reported
+/// by the child, or none once the pipe is closed without a report.
+fn read_failure(fd: RawFd) -> Option<ForkError> {
+    read_report(fd).map(|(step, errno)| failure_error(step, errno))
+}
+
+/// The function `read_report` returns the step and the `errno` of the
+/// next report, or none once the pipe is closed without one.
+fn read_report(fd: RawFd) -> Option<(u8, libc::c_int)> {
+    let mut report = [0u8; FAILURE_LEN];
+
+    loop {
+        match unsafe { libc::read(fd, report.as_mut_ptr() as *mut libc::c_void, FAILURE_LEN) } {
+            -1 if ::errno::errno().0 == libc::EINTR => continue,
+            len if len == FAILURE_LEN as isize => break,
+            _ => return None,
+        }
+    }
+
+    let mut errno = [0u8; FAILURE_LEN - 1];
+
+    errno.copy_from_slice(&report[1..]);
+    Some((report[0], libc::c_int::from_ne_bytes(errno)))
+}
+
+/// The function `failure_error` returns the error for the failed `step`
+/// with its `errno`.
+fn failure_error(step: u8, errno: libc::c_int) -> ForkError {
+    let err = io::Error::from_raw_os_error(errno);
+
+    match step {
+        SETGROUPS => ForkError::SetgroupsFail(err),
+        SETGID => ForkError::SetgidFail(err),
+        SETUID => ForkError::SetuidFail(err),
+        CHDIR => ForkError::ChdirFail(err),
+        INHERIT_FD => ForkError::InheritFdFail(err),
+        WINDOW_SIZE => ForkError::WindowSizeFail(err),
+        FORK => ForkError::Failure(err),
+        SETSID => ForkError::SetsidFail(err),
+        ATTACH => ForkError::AttachFail(err),
+        _ => ForkError::ExecFail(err),
+    }
+}
+
+/// The function `inherit_fd` duplicates `source` onto `child_fd`, which
+/// `dup2` leaves close-on-exec if they're the same fd.
+unsafe fn inherit_fd(child_fd: RawFd, source: RawFd) -> libc::c_int {
+    if child_fd != source {
+        libc::dup2(source, child_fd)
+    } else {
+        match libc::fcntl(source, libc::F_GETFD) {
+            -1 => -1,
+            flags => libc::fcntl(source, libc::F_SETFD, flags & !libc::FD_CLOEXEC),
+        }
+    }
+}
+
+/// The function `reset_signals` restores the default disposition of every
+/// signal, those which can't be changed are skipped, and clears the mask.
+unsafe fn reset_signals() {
+    let mut mask = mem::zeroed();
+
+    for signal in 1..SIGNALS {
+        libc::signal(signal, libc::SIG_DFL);
+    }
+    libc::sigemptyset(&mut mask);
+    libc::sigprocmask(libc::SIG_SETMASK, &mask, ptr::null_mut());
+}
@@ -0,0 +1,45 @@
+use ::libc;
+
+use super::{ForkError, Master, Result};
+use std::io;
+
+/// The struct `Detached` is a session spawned by `spawn_detached`, its
+/// process isn't a child of the caller so it can't be waited for.
+#[derive(Debug)]
+pub struct Detached {
+    pid: libc::pid_t,
+    master: Master,
+}
+
+impl Detached {
+    /// The constructor function `new` returns the session of the
+    /// process `pid` with its `master`.
+    pub(crate) fn new(pid: libc::pid_t, master: Master) -> Self {
+        Detached { pid, master }
+    }
+
+    /// The accessor function `pid` returns the pid of the session leader.
+    pub fn pid(&self) -> libc::pid_t {
+        self.pid
+    }
+
+    /// The accessor function `master` borrows the master of the session.
+    pub fn master(&self) -> &Master {
+        &self.master
+    }
+
+    /// The function `into_master` returns the master of the session.
+    pub fn into_master(self) -> Master {
+        self.master
+    }
+
+    /// Sends the signal `signal` to the session leader.
+    pub fn kill(&self, signal: libc::c_int) -> Result<()> {
+        unsafe {
+            match libc::kill(self.pid, signal) {
+                -1 => Err(ForkError::KillFail(io::Error::last_os_error())),
+                _ => Ok(()),
+            }
+        }
+    }
+}
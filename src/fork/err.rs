@@ -1,26 +1,48 @@
 use ::descriptor::DescriptorError;
 use std::error::Error;
 use std::fmt;
+use std::io;
 
 use super::pty::{MasterError, SlaveError};
 
 /// The alias `Result` learns `ForkError` possibility.
-
 pub type Result<T> = ::std::result::Result<T, ForkError>;
 
 /// The enum `ForkError` defines the possible errors from constructor Fork.
-#[derive(Clone, Copy, Debug)]
+#[derive(Debug)]
 pub enum ForkError {
     /// Can't creates the child.
-    Failure,
+    Failure(io::Error),
     /// Can't set the id group.
-    SetsidFail,
+    SetsidFail(io::Error),
     /// Can't suspending the calling process.
-    WaitpidFail,
+    WaitpidFail(io::Error),
+    /// The child has already been reaped or isn't a child anymore.
+    NoChild,
+    /// Can't send the signal to the child.
+    KillFail(io::Error),
+    /// Can't change the working directory of the child.
+    ChdirFail(io::Error),
+    /// Can't duplicate an inherited fd in the child.
+    InheritFdFail(io::Error),
+    /// Can't attach the slave as the terminal of the child.
+    AttachFail(io::Error),
+    /// Can't set the window size of the child.
+    WindowSizeFail(io::Error),
+    /// Can't set the supplementary groups of the child.
+    SetgroupsFail(io::Error),
+    /// Can't set the group id of the child.
+    SetgidFail(io::Error),
+    /// Can't set the user id of the child.
+    SetuidFail(io::Error),
+    /// Can't execute the command in the child.
+    ExecFail(io::Error),
     /// Is child and not parent.
     IsChild,
     /// Is parent and not child.
     IsParent,
+    /// A command, argument or environment variable contains a nul byte.
+    InvalidString,
     /// The Master occured a error.
     BadMaster(MasterError),
     /// The Slave occured a error.
@@ -33,27 +55,57 @@ pub enum ForkError {
 
 impl fmt::Display for ForkError {
     /// The function `fmt` formats the value using the given formatter.
-
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", ::errno::errno())
+        match *self {
+            ForkError::Failure(ref err) |
+            ForkError::SetsidFail(ref err) |
+            ForkError::WaitpidFail(ref err) |
+            ForkError::KillFail(ref err) |
+            ForkError::ChdirFail(ref err) |
+            ForkError::InheritFdFail(ref err) |
+            ForkError::AttachFail(ref err) |
+            ForkError::WindowSizeFail(ref err) |
+            ForkError::SetgroupsFail(ref err) |
+            ForkError::SetgidFail(ref err) |
+            ForkError::SetuidFail(ref err) |
+            ForkError::ExecFail(ref err) => write!(f, "{}", err),
+            ForkError::NoChild => write!(f, "no such child"),
+            ForkError::IsChild => write!(f, "is child and not parent"),
+            ForkError::IsParent => write!(f, "is parent and not child"),
+            ForkError::InvalidString => write!(f, "the string contains a nul byte"),
+            ForkError::BadMaster(ref err) => write!(f, "{}", err),
+            ForkError::BadSlave(ref err) => write!(f, "{}", err),
+            ForkError::BadDescriptorMaster(ref err) |
+            ForkError::BadDescriptorSlave(ref err) => write!(f, "{}", err),
+        }
     }
 }
 
 impl Error for ForkError {
     /// The function `description` returns a short description of the error.
-
     fn description(&self) -> &str {
         match *self {
-            ForkError::Failure => {
+            ForkError::Failure(_) => {
                 "On failure, -1 is returned in the parent,no child process is created, and errno \
                  isset appropriately."
             }
-            ForkError::SetsidFail => {
+            ForkError::SetsidFail(_) => {
                 "fails if the calling process is alreadya process group leader."
             }
-            ForkError::WaitpidFail => "Can't suspending the calling process.",
+            ForkError::WaitpidFail(_) => "Can't suspending the calling process.",
+            ForkError::NoChild => "the child has already been reaped or isn't a child anymore",
+            ForkError::KillFail(_) => "Can't send the signal to the child.",
+            ForkError::ChdirFail(_) => "Can't change the working directory of the child.",
+            ForkError::InheritFdFail(_) => "Can't duplicate an inherited fd in the child.",
+            ForkError::AttachFail(_) => "Can't attach the slave as the terminal of the child.",
+            ForkError::WindowSizeFail(_) => "Can't set the window size of the child.",
+            ForkError::SetgroupsFail(_) => "Can't set the supplementary groups of the child.",
+            ForkError::SetgidFail(_) => "Can't set the group id of the child.",
+            ForkError::SetuidFail(_) => "Can't set the user id of the child.",
+            ForkError::ExecFail(_) => "Can't execute the command in the child.",
             ForkError::IsChild => "is child and not parent",
             ForkError::IsParent => "is parent and not child",
+            ForkError::InvalidString => "the string contains a nul byte",
             ForkError::BadMaster(_) => "the master as occured an error",
             ForkError::BadSlave(_) => "the slave as occured an error",
             ForkError::BadDescriptorMaster(_) => "the master's descriptor as occured an error",
@@ -63,9 +115,20 @@ impl Error for ForkError {
     }
 
     /// The function `cause` returns the lower-level cause of this error, if any.
-
-    fn cause(&self) -> Option<&Error> {
+    fn cause(&self) -> Option<&dyn Error> {
         match *self {
+            ForkError::Failure(ref err) => Some(err),
+            ForkError::SetsidFail(ref err) => Some(err),
+            ForkError::WaitpidFail(ref err) => Some(err),
+            ForkError::KillFail(ref err) => Some(err),
+            ForkError::ChdirFail(ref err) => Some(err),
+            ForkError::InheritFdFail(ref err) => Some(err),
+            ForkError::AttachFail(ref err) => Some(err),
+            ForkError::WindowSizeFail(ref err) => Some(err),
+            ForkError::SetgroupsFail(ref err) => Some(err),
+            ForkError::SetgidFail(ref err) => Some(err),
+            ForkError::SetuidFail(ref err) => Some(err),
+            ForkError::ExecFail(ref err) => Some(err),
             ForkError::BadMaster(ref err) => Some(err),
             ForkError::BadSlave(ref err) => Some(err),
             ForkError::BadDescriptorMaster(ref err) => Some(err),
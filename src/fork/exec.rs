@@ -0,0 +1,24 @@
+use ::libc;
+use std::ffi::CString;
+use std::io;
+use std::ptr;
+
+/// The function `exec` replaces the program with the command `cmd`,
+/// searched in the `PATH`, and the arguments `args`, which is meant for
+/// the child of a fork. It only returns on failure, with the error.
+pub fn exec(cmd: &str, args: &[&str]) -> io::Error {
+    let mut argv = Vec::with_capacity(args.len() + 1);
+
+    for arg in Some(&cmd).into_iter().chain(args) {
+        match CString::new(*arg) {
+            Ok(arg) => argv.push(arg),
+            Err(err) => return io::Error::new(io::ErrorKind::InvalidInput, err),
+        }
+    }
+
+    let mut ptrs: Vec<*const libc::c_char> = argv.iter().map(|arg| arg.as_ptr()).collect();
+    ptrs.push(ptr::null());
+
+    unsafe { libc::execvp(ptrs[0], ptrs.as_ptr()) };
+    io::Error::last_os_error()
+}
@@ -1,13 +1,39 @@
 mod pty;
+mod builder;
+mod detached;
 mod err;
+mod exec;
+mod reap;
+mod session;
+mod status;
+mod streams;
 
-use ::descriptor::Descriptor;
-
+use ::errno;
 use ::libc;
+pub use self::builder::ForkBuilder;
+pub use self::detached::Detached;
 pub use self::err::{ForkError, Result};
-pub use self::pty::{Master, MasterError};
-pub use self::pty::{Slave, SlaveError};
-use std::ffi::CString;
+pub use self::exec::exec;
+pub use self::reap::ReapOnDrop;
+pub use self::session::Session;
+#[cfg(feature = "tokio")]
+pub use self::pty::AsyncMaster;
+pub use self::pty::{Baud, BufferedMaster, FlushQueue, Lines, Master, MasterError,
+                    MasterReader, MasterWriter, PacketControl, ReadOutcome, TeeMaster,
+                    Termios};
+pub use self::pty::{PooledPty, PtyPool, Slave, SlaveError};
+pub use self::pty::{openpty, release_controlling_terminal};
+pub use self::status::WaitStatus;
+pub use self::streams::StdStreams;
+use std::cmp;
+use std::ffi::{CStr, CString};
+#[cfg(any(target_os = "linux", target_os = "android"))]
+use std::fs;
+use std::io;
+use std::os::unix::io::RawFd;
+use std::path::PathBuf;
+use std::thread;
+use std::time::{Duration, Instant};
 
 #[derive(Debug)]
 pub enum Fork {
@@ -21,21 +47,59 @@ impl Fork {
     /// The constructor function `new` forks the program
     /// and returns the current pid.
     pub fn new(path: &'static str) -> Result<Self> {
+        Fork::new_with_streams(path, StdStreams::default())
+    }
+
+    /// The constructor function `new_with_streams` forks the program
+    /// like `new`, the slave is then only the selected `streams` of the child.
+    ///
+    /// The parent returns once the child has attached the slave, so the
+    /// input written from then on isn't lost.
+    pub fn new_with_streams(path: &'static str, streams: StdStreams) -> Result<Self> {
+        Fork::fork_pty(path, streams, true)
+    }
+
+    /// The constructor function `fork_pty` forks the program like
+    /// `new_with_streams`, the slave is also the controlling terminal
+    /// of the child with `controlling`.
+    fn fork_pty(path: &str, streams: StdStreams, controlling: bool) -> Result<Self> {
         match Master::new(CString::new(path).ok().unwrap_or_default().as_ptr()) {
             Err(cause) => Err(ForkError::BadMaster(cause)),
             Ok(master) => unsafe {
                 if let Some(cause) = master.grantpt().err().or(master.unlockpt().err()) {
                     Err(ForkError::BadMaster(cause))
                 } else {
+                    let name = match master.ptsname() {
+                        Err(cause) => return Err(ForkError::BadMaster(cause)),
+                        Ok(name) => CStr::from_ptr(name).to_owned(),
+                    };
+                    let (reader, writer) = cloexec_pipe().map_err(ForkError::Failure)?;
+
                     match libc::fork() {
-                        -1 => Err(ForkError::Failure),
+                        -1 => {
+                            let err = io::Error::last_os_error();
+
+                            libc::close(reader);
+                            libc::close(writer);
+                            Err(ForkError::Failure(err))
+                        }
                         0 => {
-                            match master.ptsname() {
-                                Err(cause) => Err(ForkError::BadMaster(cause)),
-                                Ok(name) => Fork::from_pts(name), 
+                            libc::close(reader);
+
+                            let child = Fork::from_pts(name, streams, controlling);
+
+                            if child.is_ok() {
+                                libc::write(writer, b"\0".as_ptr() as *const libc::c_void, 1);
                             }
+                            libc::close(writer);
+                            child
+                        }
+                        pid => {
+                            libc::close(writer);
+                            wait_attached(reader);
+                            libc::close(reader);
+                            Ok(Fork::Parent(pid, master))
                         }
-                        pid => Ok(Fork::Parent(pid, master)),
                     }
                 }
             },
@@ -44,22 +108,39 @@ impl Fork {
 
     /// The constructor function `from_pts` is a private
     /// extention from the constructor function `new` who
-    /// prepares and returns the child.
-    fn from_pts(ptsname: *const ::libc::c_char) -> Result<Self> {
+    /// prepares and returns the child, in a new session with the slave
+    /// as its controlling terminal with `controlling`. The `ptsname` is
+    /// copied before `fork`, so the child doesn't allocate.
+    fn from_pts(ptsname: CString, streams: StdStreams, controlling: bool) -> Result<Self> {
         unsafe {
             if libc::setsid() == -1 {
-                Err(ForkError::SetsidFail)
+                Err(ForkError::SetsidFail(io::Error::last_os_error()))
             } else {
-                match Slave::new(ptsname) {
+                let flags = if controlling {
+                    libc::O_RDWR
+                } else {
+                    libc::O_RDWR | libc::O_NOCTTY
+                };
+
+                match Slave::open_path(ptsname, flags) {
                     Err(cause) => Err(ForkError::BadSlave(cause)),
                     Ok(slave) => {
-                        if let Some(cause) = slave.dup2(libc::STDIN_FILENO)
-                            .err()
-                            .or(slave.dup2(libc::STDOUT_FILENO)
-                                .err()
-                                .or(slave.dup2(libc::STDERR_FILENO).err())) {
-                            Err(ForkError::BadSlave(cause))
+                        let acquired = if controlling {
+                            slave.set_controlling_terminal()
                         } else {
+                            Ok(())
+                        };
+
+                        if let Err(err) = acquired {
+                            Err(ForkError::BadSlave(SlaveError::ControllingTerminalError(err)))
+                        } else {
+                            let selected = [(streams.stdin, libc::STDIN_FILENO),
+                                            (streams.stdout, libc::STDOUT_FILENO),
+                                            (streams.stderr, libc::STDERR_FILENO)];
+
+                            for &(_, std) in selected.iter().filter(|&&(on, _)| on) {
+                                slave.dup2(std).map_err(ForkError::BadSlave)?;
+                            }
                             Ok(Fork::Child(slave))
                         }
                     }
@@ -71,20 +152,75 @@ impl Fork {
     /// The constructor function `from_ptmx` forks the program
     /// and returns the current pid for a default PTMX's path.
     pub fn from_ptmx() -> Result<Self> {
-        Fork::new(::DEFAULT_PTMX)
+        Fork::new(::default_ptmx())
+    }
+
+    /// The constructor function `from_ptmx_with_streams` forks the program
+    /// for a default PTMX's path, with the slave as the selected `streams`.
+    pub fn from_ptmx_with_streams(streams: StdStreams) -> Result<Self> {
+        Fork::new_with_streams(::default_ptmx(), streams)
+    }
+
+    /// The constructor function `spawn` forks the program for a default
+    /// PTMX's path and executes `cmd` with the arguments `args` and the
+    /// environment variables `env` in the child, so only the parent returns.
+    ///
+    /// It fails with `ForkError::ExecFail` if the command can't be executed.
+    pub fn spawn(cmd: &str, args: &[&str], env: &[(&str, &str)]) -> Result<Self> {
+        let mut builder = ForkBuilder::new();
+
+        for &(key, value) in env {
+            builder.env(key, value);
+        }
+        builder.spawn(cmd, args)
+    }
+
+    /// The constructor function `spawn_detached` spawns the command like
+    /// `spawn` in a session which isn't a child of the caller, so it
+    /// outlives it, see `ForkBuilder::spawn_detached`.
+    pub fn spawn_detached(cmd: &str, args: &[&str], env: &[(&str, &str)]) -> Result<Detached> {
+        let mut builder = ForkBuilder::new();
+
+        for &(key, value) in env {
+            builder.env(key, value);
+        }
+        builder.spawn_detached(cmd, args)
     }
 
     /// Waits until it's terminated.
     pub fn wait(&self) -> Result<libc::pid_t> {
+        match *self {
+            Fork::Child(_) => Err(ForkError::IsChild),
+            Fork::Parent(pid, _) => self.wait_status().and(Ok(pid)),
+        }
+    }
+
+    /// Waits until it's terminated and returns how the child has terminated.
+    pub fn wait_status(&self) -> Result<WaitStatus> {
         match *self {
             Fork::Child(_) => Err(ForkError::IsChild),
             Fork::Parent(pid, _) => {
+                let mut status = 0;
+
                 loop {
                     unsafe {
-                        match libc::waitpid(pid, &mut 0, 0) {
+                        match libc::waitpid(pid, &mut status, 0) {
                             0 => continue,
-                            -1 => return Err(ForkError::WaitpidFail),
-                            _ => return Ok(pid),
+                            -1 if errno::errno().0 == libc::EINTR => continue,
+                            -1 if errno::errno().0 == libc::ECHILD => {
+                                self.reaped();
+                                return Err(ForkError::NoChild)
+                            }
+                            -1 => return Err(ForkError::WaitpidFail(io::Error::last_os_error())),
+                            _ => {
+                                match WaitStatus::from_raw(status) {
+                                    Some(status) if status.is_terminated() => {
+                                        self.reaped();
+                                        return Ok(status)
+                                    }
+                                    _ => continue,
+                                }
+                            }
                         }
                     }
                 }
@@ -92,12 +228,269 @@ impl Fork {
         }
     }
 
-    /// The function `is_parent` returns the pid or parent
-    /// or none.
+    /// Waits until the child terminates, stops or continues, unlike
+    /// `wait_status` which skips over the job control, and returns the state.
+    pub fn wait_for_state(&self) -> Result<WaitStatus> {
+        match *self {
+            Fork::Child(_) => Err(ForkError::IsChild),
+            Fork::Parent(pid, _) => {
+                let mut status = 0;
+
+                loop {
+                    unsafe {
+                        match libc::waitpid(pid, &mut status, libc::WUNTRACED | libc::WCONTINUED) {
+                            0 => continue,
+                            -1 if errno::errno().0 == libc::EINTR => continue,
+                            -1 if errno::errno().0 == libc::ECHILD => {
+                                self.reaped();
+                                return Err(ForkError::NoChild)
+                            }
+                            -1 => return Err(ForkError::WaitpidFail(io::Error::last_os_error())),
+                            _ => {
+                                if let Some(status) = WaitStatus::from_raw(status) {
+                                    if status.is_terminated() {
+                                        self.reaped();
+                                    }
+                                    return Ok(status);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Waits until it's terminated and returns how the child has terminated
+    /// with its resource usage, like the CPU time and the maximum resident
+    /// set size.
+    #[cfg(any(target_os = "linux",
+              target_os = "android",
+              target_os = "macos",
+              target_os = "ios",
+              target_os = "freebsd",
+              target_os = "dragonfly",
+              target_os = "netbsd"))]
+    pub fn wait4(&self) -> Result<(WaitStatus, libc::rusage)> {
+        use std::mem;
+
+        match *self {
+            Fork::Child(_) => Err(ForkError::IsChild),
+            Fork::Parent(pid, _) => {
+                let mut status = 0;
+
+                loop {
+                    unsafe {
+                        let mut usage: libc::rusage = mem::zeroed();
+
+                        match libc::wait4(pid, &mut status, 0, &mut usage) {
+                            0 => continue,
+                            -1 if errno::errno().0 == libc::EINTR => continue,
+                            -1 if errno::errno().0 == libc::ECHILD => {
+                                self.reaped();
+                                return Err(ForkError::NoChild)
+                            }
+                            -1 => return Err(ForkError::WaitpidFail(io::Error::last_os_error())),
+                            _ => {
+                                match WaitStatus::from_raw(status) {
+                                    Some(status) if status.is_terminated() => {
+                                        self.reaped();
+                                        return Ok((status, usage))
+                                    }
+                                    _ => continue,
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Checks without blocking whether it's terminated,
+    /// returns the pid or none if the child is still alive.
+    pub fn try_wait(&self) -> Result<Option<libc::pid_t>> {
+        match *self {
+            Fork::Child(_) => Err(ForkError::IsChild),
+            Fork::Parent(pid, _) => self.try_wait_status().map(|status| status.and(Some(pid))),
+        }
+    }
+
+    /// The function `try_wait_status` checks without blocking whether
+    /// it's terminated, returns how or none if the child is still alive.
+    fn try_wait_status(&self) -> Result<Option<WaitStatus>> {
+        match *self {
+            Fork::Child(_) => Err(ForkError::IsChild),
+            Fork::Parent(pid, _) => {
+                let mut status = 0;
+
+                unsafe {
+                    match libc::waitpid(pid, &mut status, libc::WNOHANG) {
+                        0 => Ok(None),
+                        -1 if errno::errno().0 == libc::ECHILD => {
+                            self.reaped();
+                            Err(ForkError::NoChild)
+                        }
+                        -1 => Err(ForkError::WaitpidFail(io::Error::last_os_error())),
+                        _ => {
+                            self.reaped();
+                            Ok(WaitStatus::from_raw(status))
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Stops the child with `SIGTERM`, then with `SIGKILL` if it's still
+    /// alive once `grace` elapses, and returns how it has terminated.
+    pub fn terminate(&self, grace: Duration) -> Result<WaitStatus> {
+        let deadline = Instant::now() + grace;
+
+        self.kill(libc::SIGTERM)?;
+        loop {
+            if let Some(status) = self.try_wait_status()? {
+                return Ok(status);
+            }
+
+            let remaining = deadline.saturating_duration_since(Instant::now());
+
+            if remaining == Duration::from_secs(0) {
+                break;
+            }
+            thread::sleep(cmp::min(remaining, Duration::from_millis(10)));
+        }
+        self.kill(libc::SIGKILL)?;
+        self.wait_status()
+    }
+
+    /// Sends the signal `signal` to the child.
+    pub fn kill(&self, signal: libc::c_int) -> Result<()> {
+        match *self {
+            Fork::Child(_) => Err(ForkError::IsChild),
+            Fork::Parent(pid, _) => unsafe {
+                match libc::kill(pid, signal) {
+                    -1 => Err(ForkError::KillFail(io::Error::last_os_error())),
+                    _ => Ok(()),
+                }
+            },
+        }
+    }
+
+    /// Stops the child with `SIGSTOP`, until it's resumed.
+    pub fn suspend(&self) -> Result<()> {
+        self.kill(libc::SIGSTOP)
+    }
+
+    /// Continues the stopped child with `SIGCONT`.
+    pub fn resume(&self) -> Result<()> {
+        self.kill(libc::SIGCONT)
+    }
+
+    /// Checks without blocking whether the child is still running, with
+    /// `waitid` and `WNOWAIT` so an exited child isn't reaped, and still
+    /// can be waited for. A child already reaped isn't alive either.
+    pub fn is_alive(&self) -> Result<bool> {
+        match *self {
+            Fork::Child(_) => Err(ForkError::IsChild),
+            Fork::Parent(pid, _) => {
+                let options = libc::WEXITED | libc::WNOHANG | libc::WNOWAIT;
+                // The `id_t` of Solaris and illumos is the `pid_t` itself.
+                #[cfg(any(target_os = "solaris", target_os = "illumos"))]
+                let id = pid;
+                #[cfg(not(any(target_os = "solaris", target_os = "illumos")))]
+                let id = pid as libc::id_t;
+
+                loop {
+                    unsafe {
+                        let mut info: libc::siginfo_t = ::std::mem::zeroed();
+
+                        match libc::waitid(libc::P_PID, id, &mut info, options) {
+                            -1 if errno::errno().0 == libc::EINTR => continue,
+                            -1 if errno::errno().0 == libc::ECHILD => return Ok(false),
+                            -1 => return Err(ForkError::WaitpidFail(io::Error::last_os_error())),
+                            // Nothing is reported while the child is running.
+                            _ => return Ok(info.si_pid() == 0),
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the process group id of the child, which is its pid
+    /// since the child leads its own session once it's set up.
+    pub fn pgid(&self) -> Result<libc::pid_t> {
+        match *self {
+            Fork::Child(_) => Err(ForkError::IsChild),
+            Fork::Parent(pid, _) => Ok(pid),
+        }
+    }
+
+    /// Sends the signal `signal` to the process group of the child.
+    pub fn killpg(&self, signal: libc::c_int) -> Result<()> {
+        let pgid = self.pgid()?;
+
+        unsafe {
+            match libc::killpg(pgid, signal) {
+                -1 => Err(ForkError::KillFail(io::Error::last_os_error())),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    /// Returns the current working directory of the child, so it follows
+    /// the `cd` of a shell, read from `/proc/<pid>/cwd`.
+    /// It fails with `NotFound` once the child has exited.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub fn child_cwd(&self) -> io::Result<PathBuf> {
+        match *self {
+            Fork::Child(_) => Err(io::Error::other("the fork is the child, not the parent")),
+            Fork::Parent(pid, _) => {
+                fs::read_link(format!("/proc/{}/cwd", pid)).map_err(|err| {
+                    match err.raw_os_error() {
+                        Some(libc::ESRCH) => io::Error::from(io::ErrorKind::NotFound),
+                        _ => err,
+                    }
+                })
+            }
+        }
+    }
+
+    /// Returns the current working directory of the child, which fails
+    /// with `Unsupported` without procfs.
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    pub fn child_cwd(&self) -> io::Result<PathBuf> {
+        Err(io::Error::new(io::ErrorKind::Unsupported,
+                           "the working directory of the child needs procfs"))
+    }
+
+    /// The function `reaped` records that the child has been reaped, so
+    /// the master doesn't hang up its pid, which can be reused by then.
+    fn reaped(&self) {
+        if let Fork::Parent(_, ref master) = *self {
+            master.cancel_hangup();
+        }
+    }
+
+    /// The function `is_parent` returns a duplicate of the master
+    /// or none, it fails with `MasterError::CloneFail` when the fd
+    /// can't be duplicated, like with `EMFILE`.
     pub fn is_parent(&self) -> Result<Master> {
         match *self {
             Fork::Child(_) => Err(ForkError::IsChild),
-            Fork::Parent(_, ref master) => Ok(master.clone()),
+            Fork::Parent(_, ref master) => {
+                master.try_clone().map_err(|err| ForkError::BadMaster(MasterError::CloneFail(err)))
+            }
+        }
+    }
+
+    /// The function `parent` borrows the pid of the child and the master,
+    /// or none in the child, unlike `is_parent` which duplicates the master.
+    pub fn parent(&self) -> Option<(libc::pid_t, &Master)> {
+        match *self {
+            Fork::Parent(pid, ref master) => Some((pid, master)),
+            Fork::Child(_) => None,
         }
     }
 
@@ -111,11 +504,58 @@ impl Fork {
     }
 }
 
-impl Drop for Fork {
-    fn drop(&mut self) {
-        match *self {
-            Fork::Parent(_, ref master) => Descriptor::drop(master),
-            _ => {}
+/// The function `cloexec_pipe` returns the read and write ends of a pipe
+/// with close-on-exec, so the write end is closed when the child executes
+/// its command. It's set atomically with `pipe2`, so another thread which
+/// forks meanwhile doesn't inherit the write end.
+#[cfg(not(any(target_os = "macos", target_os = "ios")))]
+fn cloexec_pipe() -> io::Result<(RawFd, RawFd)> {
+    let mut fds = [0; 2];
+
+    match unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC) } {
+        -1 => Err(io::Error::last_os_error()),
+        _ => Ok((fds[0], fds[1])),
+    }
+}
+
+/// The function `cloexec_pipe` returns the read and write ends of a pipe
+/// with close-on-exec, set by `fcntl` after `pipe` since Darwin has no
+/// `pipe2`. It's racy: another thread which forks and executes in between
+/// inherits the write end, and the parent waits until that program exits.
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+fn cloexec_pipe() -> io::Result<(RawFd, RawFd)> {
+    let mut fds = [0; 2];
+
+    unsafe {
+        if libc::pipe(fds.as_mut_ptr()) == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        for &fd in &fds {
+            if libc::fcntl(fd, libc::F_SETFD, libc::FD_CLOEXEC) == -1 {
+                let err = io::Error::last_os_error();
+
+                libc::close(fds[0]);
+                libc::close(fds[1]);
+                return Err(err);
+            }
         }
     }
+    Ok((fds[0], fds[1]))
+}
+
+/// The function `wait_attached` waits until the child writes to the pipe
+/// once its slave is attached, or closes it when its setup fails.
+fn wait_attached(fd: RawFd) {
+    let mut byte = [0u8; 1];
+
+    while unsafe { libc::read(fd, byte.as_mut_ptr() as *mut libc::c_void, 1) } == -1 &&
+          errno::errno().0 == libc::EINTR {}
+}
+
+/// The function `reap` waits until the child `pid` is terminated and
+/// discards its status, it retries when a signal interrupts the wait.
+fn reap(pid: libc::pid_t) {
+    let mut status = 0;
+
+    while unsafe { libc::waitpid(pid, &mut status, 0) } == -1 && errno::errno().0 == libc::EINTR {}
 }
@@ -0,0 +1,93 @@
+use ::tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use ::tokio::io::unix::AsyncFd;
+
+use super::Master;
+use std::io;
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// The struct `AsyncMaster` registers a non-blocking master
+/// with the reactor of tokio.
+#[derive(Debug)]
+pub struct AsyncMaster {
+    inner: AsyncFd<Master>,
+}
+
+impl AsyncMaster {
+    /// The constructor function `new` sets the master non-blocking and
+    /// registers it with the current tokio runtime.
+    pub fn new(master: Master) -> io::Result<Self> {
+        master.set_nonblocking(true)?;
+        Ok(AsyncMaster { inner: AsyncFd::new(master)? })
+    }
+
+    /// The accessor function `get_ref` returns the master.
+    pub fn get_ref(&self) -> &Master {
+        self.inner.get_ref()
+    }
+
+    /// The function `into_inner` deregisters and returns the master,
+    /// which stays non-blocking.
+    pub fn into_inner(self) -> Master {
+        self.inner.into_inner()
+    }
+}
+
+impl AsRawFd for AsyncMaster {
+    /// The accessor function `as_raw_fd` returns the fd.
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.as_raw_fd()
+    }
+}
+
+impl AsyncRead for AsyncMaster {
+    fn poll_read(self: Pin<&mut Self>,
+                 cx: &mut Context<'_>,
+                 buf: &mut ReadBuf<'_>)
+                 -> Poll<io::Result<()>> {
+        loop {
+            let mut guard = match self.inner.poll_read_ready(cx) {
+                Poll::Ready(guard) => guard?,
+                Poll::Pending => return Poll::Pending,
+            };
+
+            // `try_io` clears the readiness when the read would block.
+            match guard.try_io(|inner| inner.get_ref().read_fd(buf.initialize_unfilled())) {
+                Ok(Ok(len)) => {
+                    buf.advance(len);
+                    return Poll::Ready(Ok(()));
+                }
+                Ok(Err(err)) => return Poll::Ready(Err(err)),
+                Err(_would_block) => continue,
+            }
+        }
+    }
+}
+
+impl AsyncWrite for AsyncMaster {
+    fn poll_write(self: Pin<&mut Self>,
+                  cx: &mut Context<'_>,
+                  buf: &[u8])
+                  -> Poll<io::Result<usize>> {
+        loop {
+            let mut guard = match self.inner.poll_write_ready(cx) {
+                Poll::Ready(guard) => guard?,
+                Poll::Pending => return Poll::Pending,
+            };
+
+            match guard.try_io(|inner| inner.get_ref().write_fd(buf)) {
+                Ok(result) => return Poll::Ready(result),
+                Err(_would_block) => continue,
+            }
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
@@ -0,0 +1,54 @@
+use ::libc;
+
+/// The enum `Baud` is the line speed reported by the terminal,
+/// set by `Master::set_speed`. A PTY doesn't transmit at any speed,
+/// though some programs read it with `cfgetospeed`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Baud {
+    B300,
+    B1200,
+    B2400,
+    B4800,
+    B9600,
+    B19200,
+    B38400,
+    B57600,
+    B115200,
+    B230400,
+}
+
+impl Baud {
+    /// The constructor function `from_raw` returns the speed
+    /// for the `libc` constant `speed`, or none for another one.
+    pub fn from_raw(speed: libc::speed_t) -> Option<Self> {
+        match speed {
+            libc::B300 => Some(Baud::B300),
+            libc::B1200 => Some(Baud::B1200),
+            libc::B2400 => Some(Baud::B2400),
+            libc::B4800 => Some(Baud::B4800),
+            libc::B9600 => Some(Baud::B9600),
+            libc::B19200 => Some(Baud::B19200),
+            libc::B38400 => Some(Baud::B38400),
+            libc::B57600 => Some(Baud::B57600),
+            libc::B115200 => Some(Baud::B115200),
+            libc::B230400 => Some(Baud::B230400),
+            _ => None,
+        }
+    }
+
+    /// The accessor function `as_raw` returns the `libc` constant.
+    pub fn as_raw(&self) -> libc::speed_t {
+        match *self {
+            Baud::B300 => libc::B300,
+            Baud::B1200 => libc::B1200,
+            Baud::B2400 => libc::B2400,
+            Baud::B4800 => libc::B4800,
+            Baud::B9600 => libc::B9600,
+            Baud::B19200 => libc::B19200,
+            Baud::B38400 => libc::B38400,
+            Baud::B57600 => libc::B57600,
+            Baud::B115200 => libc::B115200,
+            Baud::B230400 => libc::B230400,
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use super::Master;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::os::unix::io::{AsRawFd, RawFd};
+
+/// The struct `BufferedMaster` buffers the reads from a `Master` while
+/// the writes go straight to its fd, returned by `Master::buffered`.
+#[derive(Debug)]
+pub struct BufferedMaster {
+    reader: BufReader<Master>,
+}
+
+impl BufferedMaster {
+    /// The constructor function `new` takes ownership of `master`.
+    pub fn new(master: Master) -> Self {
+        BufferedMaster { reader: BufReader::new(master) }
+    }
+
+    /// The constructor function `with_capacity` takes ownership of `master`
+    /// and buffers up to `capacity` bytes, instead of the default 8 KiB.
+    pub fn with_capacity(capacity: usize, master: Master) -> Self {
+        BufferedMaster { reader: BufReader::with_capacity(capacity, master) }
+    }
+
+    /// The accessor function `capacity` returns the size of the buffer.
+    pub fn capacity(&self) -> usize {
+        self.reader.capacity()
+    }
+
+    /// The accessor function `get_ref` returns the master.
+    pub fn get_ref(&self) -> &Master {
+        self.reader.get_ref()
+    }
+
+    /// The function `into_inner` returns the master,
+    /// the data still buffered is lost.
+    pub fn into_inner(self) -> Master {
+        self.reader.into_inner()
+    }
+}
+
+impl AsRawFd for BufferedMaster {
+    /// The accessor function `as_raw_fd` returns the fd.
+    fn as_raw_fd(&self) -> RawFd {
+        self.reader.get_ref().as_raw_fd()
+    }
+}
+
+impl Read for BufferedMaster {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.reader.read(buf)
+    }
+}
+
+impl BufRead for BufferedMaster {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.reader.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.reader.consume(amt)
+    }
+}
+
+impl Write for BufferedMaster {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.reader.get_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.reader.get_mut().flush()
+    }
+}
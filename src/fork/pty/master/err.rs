@@ -1,47 +1,87 @@
-use ::descriptor::DescriptorError;
 use std::error::Error;
 use std::fmt;
+use std::io;
 
 /// The alias `Result` learns `MasterError` possibility.
-
 pub type Result<T> = ::std::result::Result<T, MasterError>;
 
-/// The enum `MasterError` defines the possible errors from constructor Master.
-#[derive(Clone, Copy, Debug)]
+/// The enum `MasterError` defines the possible errors from constructor Master,
+/// each with the error of the step which failed.
+#[derive(Debug)]
 pub enum MasterError {
-    BadDescriptor(DescriptorError),
-    GrantptError,
-    UnlockptError,
-    PtsnameError,
+    /// Can't open the master.
+    OpenPtFail(io::Error),
+    /// Can't grant the access to the slave.
+    GrantPtFail(io::Error),
+    /// Can't unlock the slave.
+    UnlockPtFail(io::Error),
+    /// Can't get the name of the slave.
+    PtsNameFail(io::Error),
+    /// Can't get the window size of the terminal.
+    WindowSizeFail(io::Error),
+    /// Can't duplicate the master.
+    CloneFail(io::Error),
+}
+
+impl MasterError {
+    /// The accessor function `io_error` returns the error of the step.
+    pub fn io_error(&self) -> &io::Error {
+        match *self {
+            MasterError::OpenPtFail(ref err) |
+            MasterError::GrantPtFail(ref err) |
+            MasterError::UnlockPtFail(ref err) |
+            MasterError::PtsNameFail(ref err) |
+            MasterError::WindowSizeFail(ref err) |
+            MasterError::CloneFail(ref err) => err,
+        }
+    }
 }
 
 impl fmt::Display for MasterError {
     /// The function `fmt` formats the value using the given formatter.
-
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", ::errno::errno())
+        let step = match *self {
+            MasterError::OpenPtFail(_) => "open",
+            MasterError::GrantPtFail(_) => "grantpt",
+            MasterError::UnlockPtFail(_) => "unlockpt",
+            MasterError::PtsNameFail(_) => "ptsname",
+            MasterError::WindowSizeFail(_) => "TIOCGWINSZ",
+            MasterError::CloneFail(_) => "F_DUPFD_CLOEXEC",
+        };
+
+        write!(f, "{}: {}", step, self.io_error())
     }
 }
 
 impl Error for MasterError {
     /// The function `description` returns a short description of the error.
-
     fn description(&self) -> &str {
         match *self {
-            MasterError::BadDescriptor(_) => "the descriptor as occured an error",
-            MasterError::GrantptError => "the `grantpt` has a error, errnois set appropriately.",
-            MasterError::UnlockptError => "the `grantpt` has a error, errnois set appropriately.",
-            MasterError::PtsnameError => "the `ptsname` has a error",
-
+            MasterError::OpenPtFail(_) => "the master can't be opened",
+            MasterError::GrantPtFail(_) => "the `grantpt` has a error",
+            MasterError::UnlockPtFail(_) => "the `unlockpt` has a error",
+            MasterError::PtsNameFail(_) => "the `ptsname` has a error",
+            MasterError::WindowSizeFail(_) => "the window size can't be read",
+            MasterError::CloneFail(_) => "the master can't be duplicated",
         }
     }
 
     /// The function `cause` returns the lower-level cause of this error, if any.
+    fn cause(&self) -> Option<&dyn Error> {
+        Some(self.io_error())
+    }
+}
 
-    fn cause(&self) -> Option<&Error> {
-        match *self {
-            MasterError::BadDescriptor(ref err) => Some(err),
-            _ => None,
+impl From<MasterError> for io::Error {
+    /// The function `from` returns the error of the step.
+    fn from(err: MasterError) -> io::Error {
+        match err {
+            MasterError::OpenPtFail(err) |
+            MasterError::GrantPtFail(err) |
+            MasterError::UnlockPtFail(err) |
+            MasterError::PtsNameFail(err) |
+            MasterError::WindowSizeFail(err) |
+            MasterError::CloneFail(err) => err,
         }
     }
 }
@@ -0,0 +1,51 @@
+use super::Master;
+use std::io::{self, BufRead, BufReader};
+
+/// The struct `Lines` is an iterator over the lines read from a `Master`,
+/// returned by `Master::lines`. The data it has read past the last line
+/// returned goes back to the master when it's dropped.
+#[derive(Debug)]
+pub struct Lines<'a> {
+    reader: BufReader<&'a Master>,
+}
+
+impl<'a> Lines<'a> {
+    /// The constructor function `new` buffers the reads from `master`.
+    pub fn new(master: &'a Master) -> Self {
+        Lines { reader: BufReader::new(master) }
+    }
+}
+
+impl<'a> Iterator for Lines<'a> {
+    type Item = io::Result<String>;
+
+    /// The function `next` returns the next line without its `\n`
+    /// or the `\r\n` the terminal outputs.
+    fn next(&mut self) -> Option<io::Result<String>> {
+        let mut line = String::new();
+
+        match self.reader.read_line(&mut line) {
+            Err(e) => Some(Err(e)),
+            Ok(0) => None,
+            Ok(_) => {
+                if line.ends_with('\n') {
+                    line.pop();
+                    if line.ends_with('\r') {
+                        line.pop();
+                    }
+                }
+                Some(Ok(line))
+            }
+        }
+    }
+}
+
+impl<'a> Drop for Lines<'a> {
+    /// The destructor function `drop` gives the data buffered but not
+    /// returned back to the master, so the next read returns it.
+    fn drop(&mut self) {
+        let master: &Master = self.reader.get_ref();
+
+        master.unread(self.reader.buffer());
+    }
+}
@@ -1,23 +1,116 @@
+#[cfg(feature = "tokio")]
+mod async_master;
+mod baud;
+mod buffered;
 mod err;
+mod lines;
+mod outcome;
+mod packet;
+mod queue;
+#[cfg(feature = "mio")]
+mod source;
+mod split;
+mod tee;
+mod termios;
 
 use ::{libc, ffi};
 
 use ::descriptor::Descriptor;
 
+use super::Slave;
+
+#[cfg(feature = "tokio")]
+pub use self::async_master::AsyncMaster;
+pub use self::baud::Baud;
+pub use self::buffered::BufferedMaster;
 pub use self::err::{MasterError, Result};
+pub use self::lines::Lines;
+pub use self::outcome::ReadOutcome;
+pub use self::packet::PacketControl;
+pub use self::queue::FlushQueue;
+pub use self::split::{MasterReader, MasterWriter};
+pub use self::tee::TeeMaster;
+pub use self::termios::Termios;
 use std::io;
-use std::os::unix::io::{AsRawFd, RawFd};
+use std::cmp;
+use std::ffi::{CStr, CString};
+use std::fs::File;
+use std::mem;
+use std::str;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
+use std::time::{Duration, Instant};
+
+/// The flags the master is opened with.
+#[cfg(not(any(target_os = "macos", target_os = "ios")))]
+const OPEN_FLAGS: libc::c_int = libc::O_RDWR | libc::O_CLOEXEC;
+
+/// The flags the master is opened with, Darwin also needs `O_NOCTTY`
+/// so the master doesn't become the controlling terminal of the caller.
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+const OPEN_FLAGS: libc::c_int = libc::O_RDWR | libc::O_CLOEXEC | libc::O_NOCTTY;
 
-#[derive(Debug, Copy, Clone)]
+/// The most bytes kept by `Master::peek`.
+const PEEK_CAPACITY: usize = 4096;
+
+/// The most buffers passed to a `readv` or `writev` at once,
+/// the `IOV_MAX` of Linux and macOS.
+const MAX_IOVECS: usize = 1024;
+
+/// The struct `Master` owns the fd of a PTY master, which is closed on drop.
+///
+/// It's `Send` and `Sync`, like a `File`: the fd is only an index owned by
+/// the process, the kernel serializes the calls on it from any thread, and
+/// the buffers kept for `read_text` and `peek` are behind a `Mutex`. So it
+/// can be moved into a reader thread while another one waits for the child.
+#[derive(Debug)]
 pub struct Master {
     pty: RawFd,
+    hangup: Mutex<Option<libc::pid_t>>,
+    partial_text: Mutex<Vec<u8>>,
+    peeked: Mutex<Vec<u8>>,
+    write_closed: AtomicBool,
+    bytes_read: AtomicU64,
+    bytes_written: AtomicU64,
 }
 
 impl Master {
+    /// The constructor function `new` opens the master with `O_CLOEXEC`,
+    /// so it doesn't leak into the programs executed later.
     pub fn new(path: *const ::libc::c_char) -> Result<Self> {
-        match Self::open(path, libc::O_RDWR, None) {
-            Err(cause) => Err(MasterError::BadDescriptor(cause)),
-            Ok(fd) => Ok(Master { pty: fd }),
+        Self::open_ptm_with(path, 0)
+    }
+
+    /// The constructor function `open_with` opens the default PTMX with
+    /// `flags` added to the usual ones, like `O_NOCTTY` or `O_NONBLOCK`.
+    /// The access mode is always `O_RDWR`, so only the status flags
+    /// which apply to a terminal are meaningful.
+    pub fn open_with(flags: libc::c_int) -> Result<Self> {
+        let path = CString::new(::default_ptmx()).unwrap_or_default();
+
+        Self::open_ptm_with(path.as_ptr(), flags)
+    }
+
+    /// The constructor function `with_fd` wraps the opened `fd`.
+    fn with_fd(fd: RawFd) -> Self {
+        Master {
+            pty: fd,
+            hangup: Mutex::new(None),
+            partial_text: Mutex::new(Vec::new()),
+            peeked: Mutex::new(Vec::new()),
+            write_closed: AtomicBool::new(false),
+            bytes_read: AtomicU64::new(0),
+            bytes_written: AtomicU64::new(0),
+        }
+    }
+
+    /// The function `open_ptm_with` opens the master at `path` with
+    /// `flags` besides `OPEN_FLAGS`.
+    fn open_ptm_with(path: *const ::libc::c_char, flags: libc::c_int) -> Result<Self> {
+        match Self::open(path, OPEN_FLAGS | flags, None) {
+            Err(_) => Err(MasterError::OpenPtFail(io::Error::last_os_error())),
+            Ok(fd) => Ok(Master::with_fd(fd)),
         }
     }
 
@@ -26,7 +119,7 @@ impl Master {
     pub fn grantpt(&self) -> Result<libc::c_int> {
         unsafe {
             match ffi::grantpt(self.as_raw_fd()) {
-                -1 => Err(MasterError::GrantptError),
+                -1 => Err(MasterError::GrantPtFail(io::Error::last_os_error())),
                 c => Ok(c),
             }
         }
@@ -36,7 +129,7 @@ impl Master {
     pub fn unlockpt(&self) -> Result<libc::c_int> {
         unsafe {
             match ffi::unlockpt(self.as_raw_fd()) {
-                -1 => Err(MasterError::UnlockptError),
+                -1 => Err(MasterError::UnlockPtFail(io::Error::last_os_error())),
                 c => Ok(c),
             }
         }
@@ -47,11 +140,1061 @@ impl Master {
     pub fn ptsname(&self) -> Result<*const libc::c_schar> {
         unsafe {
             match ffi::ptsname(self.as_raw_fd()) {
-                c if c.is_null() => Err(MasterError::PtsnameError),
+                c if c.is_null() => Err(MasterError::PtsNameFail(io::Error::last_os_error())),
                 c => Ok(c),
             }
         }
     }
+
+    /// Returns the slave's path, like `/dev/pts/3`, with the reentrant
+    /// `ptsname_r` so it's safe to call from multiple threads.
+    #[cfg(any(target_os = "linux", target_os = "android", target_os = "freebsd"))]
+    pub fn pts_name(&self) -> io::Result<String> {
+        let mut buf: [libc::c_char; 128] = [0; 128];
+
+        unsafe {
+            match ffi::ptsname_r(self.as_raw_fd(), buf.as_mut_ptr(), buf.len()) {
+                0 => Ok(CStr::from_ptr(buf.as_ptr()).to_string_lossy().into_owned()),
+                errno => Err(io::Error::from_raw_os_error(errno)),
+            }
+        }
+    }
+
+    /// Returns an owned copy of the slave's path, like `/dev/pts/3`,
+    /// with `ptsname` where `ptsname_r` isn't available.
+    #[cfg(not(any(target_os = "linux", target_os = "android", target_os = "freebsd")))]
+    pub fn pts_name(&self) -> io::Result<String> {
+        match self.ptsname() {
+            Err(_) => Err(io::Error::last_os_error()),
+            Ok(name) => Ok(unsafe { CStr::from_ptr(name) }.to_string_lossy().into_owned()),
+        }
+    }
+
+    /// Opens a new handle to the slave with `O_NOCTTY`, so it doesn't
+    /// become the controlling terminal of the caller, and `O_CLOEXEC`, so
+    /// it doesn't leak into children, once `grantpt` and `unlockpt` are
+    /// called.
+    pub fn open_slave(&self) -> io::Result<Slave> {
+        let path = CString::new(self.pts_name()?)?;
+
+        match Slave::open(path.as_ptr(), libc::O_RDWR | libc::O_NOCTTY | libc::O_CLOEXEC, None) {
+            Ok(fd) => Ok(Slave::with_fd(fd, Some(path))),
+            Err(_) => Err(io::Error::last_os_error()),
+        }
+    }
+
+    /// Changes the owner of the slave device to `uid` and `gid`, as `grantpt`
+    /// gives it to the caller, for a child running as another user. It must
+    /// be called before the child opens the slave and, unless the owner is
+    /// unchanged, requires the privileges of `chown`.
+    pub fn chown_slave(&self, uid: libc::uid_t, gid: libc::gid_t) -> io::Result<()> {
+        let path = CString::new(self.pts_name()?)?;
+
+        unsafe {
+            match libc::chown(path.as_ptr(), uid, gid) {
+                -1 => Err(io::Error::last_os_error()),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    /// Sets the window size of the terminal to `rows` and `cols`,
+    /// the pixel dimensions are left at zero.
+    pub fn set_window_size(&self, rows: u16, cols: u16) -> io::Result<()> {
+        let size = libc::winsize {
+            ws_row: rows,
+            ws_col: cols,
+            ws_xpixel: 0,
+            ws_ypixel: 0,
+        };
+
+        unsafe {
+            match libc::ioctl(self.as_raw_fd(), libc::TIOCSWINSZ, &size) {
+                -1 => Err(io::Error::last_os_error()),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    /// Returns the window size of the terminal as `(rows, cols)`.
+    pub fn window_size(&self) -> io::Result<(u16, u16)> {
+        let mut size = libc::winsize {
+            ws_row: 0,
+            ws_col: 0,
+            ws_xpixel: 0,
+            ws_ypixel: 0,
+        };
+
+        unsafe {
+            match libc::ioctl(self.as_raw_fd(), libc::TIOCGWINSZ, &mut size) {
+                -1 => Err(io::Error::last_os_error()),
+                _ => Ok((size.ws_row, size.ws_col)),
+            }
+        }
+    }
+
+    /// Copies the window size of the terminal `fd` (typically the
+    /// parent's stdin) to this terminal, which raises `SIGWINCH` in the child.
+    ///
+    /// The library doesn't watch for resizes itself: the caller is
+    /// responsible for installing a `SIGWINCH` handler and calling
+    /// this method from it.
+    pub fn resize_from(&self, fd: RawFd) -> io::Result<()> {
+        let mut size = libc::winsize {
+            ws_row: 0,
+            ws_col: 0,
+            ws_xpixel: 0,
+            ws_ypixel: 0,
+        };
+
+        unsafe {
+            if libc::ioctl(fd, libc::TIOCGWINSZ, &mut size) == -1 {
+                return Err(io::Error::last_os_error());
+            }
+            match libc::ioctl(self.as_raw_fd(), libc::TIOCSWINSZ, &size) {
+                -1 => Err(io::Error::last_os_error()),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    /// Returns the foreground process group of the terminal, the job
+    /// which receives the signals of the control characters like `^C`.
+    pub fn foreground_pgrp(&self) -> io::Result<libc::pid_t> {
+        unsafe {
+            match libc::tcgetpgrp(self.as_raw_fd()) {
+                -1 => Err(io::Error::last_os_error()),
+                pgid => Ok(pgid),
+            }
+        }
+    }
+
+    /// Makes `pgid` the foreground process group of the terminal. The
+    /// kernel only allows it to the processes of the session, Linux
+    /// refuses it on the master of another session with `ENOTTY`.
+    pub fn set_foreground_pgrp(&self, pgid: libc::pid_t) -> io::Result<()> {
+        unsafe {
+            match libc::tcsetpgrp(self.as_raw_fd(), pgid) {
+                -1 => Err(io::Error::last_os_error()),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    /// Puts the terminal into raw mode and returns the previous
+    /// settings, so they can be restored with `set_termios`.
+    pub fn set_raw(&self) -> io::Result<libc::termios> {
+        let previous = self.tcgetattr()?;
+        let mut raw = previous;
+
+        unsafe { libc::cfmakeraw(&mut raw) };
+        self.set_termios(&raw)?;
+        Ok(previous)
+    }
+
+    /// Applies the terminal settings `termios` immediately.
+    pub fn set_termios(&self, termios: &libc::termios) -> io::Result<()> {
+        unsafe {
+            match libc::tcsetattr(self.as_raw_fd(), libc::TCSANOW, termios) {
+                -1 => Err(io::Error::last_os_error()),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    /// Returns the current terminal settings.
+    pub fn termios(&self) -> io::Result<Termios> {
+        self.tcgetattr().map(Termios::from_raw)
+    }
+
+    /// Applies the terminal settings `termios` immediately.
+    pub fn apply(&self, termios: &Termios) -> io::Result<()> {
+        self.set_termios(termios.as_raw())
+    }
+
+    /// Applies the usual settings of `Termios::sane`, like `stty sane`,
+    /// to recover a terminal left in raw mode or without echo.
+    pub fn reset(&self) -> io::Result<()> {
+        self.apply(&Termios::sane())
+    }
+
+    /// Sets both the input and the output speed of the terminal to `baud`.
+    pub fn set_speed(&self, baud: Baud) -> io::Result<()> {
+        let mut termios = self.tcgetattr()?;
+
+        unsafe {
+            if libc::cfsetispeed(&mut termios, baud.as_raw()) == -1 ||
+               libc::cfsetospeed(&mut termios, baud.as_raw()) == -1 {
+                return Err(io::Error::last_os_error());
+            }
+        }
+        self.set_termios(&termios)
+    }
+
+    /// Returns the output speed of the terminal, or none if it isn't
+    /// one of `Baud`.
+    pub fn speed(&self) -> io::Result<Option<Baud>> {
+        let termios = self.tcgetattr()?;
+
+        Ok(Baud::from_raw(unsafe { libc::cfgetospeed(&termios) }))
+    }
+
+    /// Toggles the non-blocking mode of the fd, a read without
+    /// data available then fails with `io::ErrorKind::WouldBlock`.
+    /// It's required before registering the master with `mio`.
+    pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        unsafe {
+            let flags = match libc::fcntl(self.as_raw_fd(), libc::F_GETFL) {
+                -1 => return Err(io::Error::last_os_error()),
+                flags if nonblocking => flags | libc::O_NONBLOCK,
+                flags => flags & !libc::O_NONBLOCK,
+            };
+
+            match libc::fcntl(self.as_raw_fd(), libc::F_SETFL, flags) {
+                -1 => Err(io::Error::last_os_error()),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    /// Toggles the signal-driven I/O of the fd with `O_ASYNC`, so the kernel
+    /// sends `SIGIO` to the process or, when negative, the process group
+    /// `owner` once the master is readable or writable. The caller installs
+    /// the `SIGIO` handler first, as its default action terminates the
+    /// process, and the handler can only call async-signal-safe functions.
+    /// Solaris and illumos, without `O_ASYNC`, don't have it.
+    #[cfg(not(any(target_os = "solaris", target_os = "illumos")))]
+    pub fn set_async(&self, on: bool, owner: libc::pid_t) -> io::Result<()> {
+        unsafe {
+            if on && libc::fcntl(self.as_raw_fd(), libc::F_SETOWN, owner) == -1 {
+                return Err(io::Error::last_os_error());
+            }
+
+            let flags = match libc::fcntl(self.as_raw_fd(), libc::F_GETFL) {
+                -1 => return Err(io::Error::last_os_error()),
+                flags if on => flags | libc::O_ASYNC,
+                flags => flags & !libc::O_ASYNC,
+            };
+
+            match libc::fcntl(self.as_raw_fd(), libc::F_SETFL, flags) {
+                -1 => Err(io::Error::last_os_error()),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    /// Toggles the close-on-exec flag of the fd.
+    pub fn set_cloexec(&self, on: bool) -> io::Result<()> {
+        unsafe {
+            let flags = match libc::fcntl(self.as_raw_fd(), libc::F_GETFD) {
+                -1 => return Err(io::Error::last_os_error()),
+                flags if on => flags | libc::FD_CLOEXEC,
+                flags => flags & !libc::FD_CLOEXEC,
+            };
+
+            match libc::fcntl(self.as_raw_fd(), libc::F_SETFD, flags) {
+                -1 => Err(io::Error::last_os_error()),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    /// Splits into independent read and write halves, each holding
+    /// its own duplicate of the fd so the terminal stays open until
+    /// both halves are closed. The reader keeps the data already read
+    /// by `peek` and `read_text`, the writer stays closed after
+    /// `close_write`.
+    pub fn split(mut self) -> io::Result<(MasterReader, MasterWriter)> {
+        let mut reader = self.try_clone()?;
+        let writer = self.try_clone()?;
+
+        reader.hangup = mem::take(&mut self.hangup);
+        reader.peeked = mem::take(&mut self.peeked);
+        reader.partial_text = mem::take(&mut self.partial_text);
+        writer.write_closed.store(self.write_closed.load(Ordering::SeqCst), Ordering::SeqCst);
+        reader.bytes_read.store(self.bytes_read(), Ordering::Relaxed);
+        writer.bytes_written.store(self.bytes_written(), Ordering::Relaxed);
+        Ok((MasterReader::new(reader), MasterWriter::new(writer)))
+    }
+
+    /// Closes the fd and returns the error, like `EIO` which can indicate
+    /// lost writes. Dropping the master closes it too but discards the error.
+    /// The child is hung up first if asked, like on drop.
+    pub fn close(mut self) -> io::Result<()> {
+        self.hang_up();
+
+        let result = self.close_fd();
+
+        // The fd is closed already, `drop` releases the rest.
+        self.pty = -1;
+        result
+    }
+
+    /// The function `hangup_on_drop` sends `SIGHUP` to the process
+    /// group `pgid` when the master is dropped, its clones don't.
+    pub(crate) fn hangup_on_drop(&mut self, pgid: libc::pid_t) {
+        self.hangup = Mutex::new(Some(pgid));
+    }
+
+    /// The function `hang_up` sends the pending `SIGHUP` of `hangup_on_drop`,
+    /// only once.
+    fn hang_up(&self) {
+        if let Some(pgid) = self.hangup.lock().unwrap_or_else(|e| e.into_inner()).take() {
+            unsafe {
+                libc::killpg(pgid, libc::SIGHUP);
+            }
+        }
+    }
+
+    /// The function `cancel_hangup` stops `drop` from sending `SIGHUP`
+    /// once the child is reaped, since its pid can be reused by then.
+    pub(crate) fn cancel_hangup(&self) {
+        self.hangup.lock().unwrap_or_else(|e| e.into_inner()).take();
+    }
+
+    /// The function `close_fd` closes the fd.
+    fn close_fd(&self) -> io::Result<()> {
+        unsafe {
+            match libc::close(self.as_raw_fd()) {
+                -1 => Err(io::Error::last_os_error()),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    /// Returns a new master owning a duplicate of the fd with close-on-exec,
+    /// which is closed independently on drop. Both handles share the PTY,
+    /// so the data read from one isn't read from the other, and only the
+    /// original hangs up the child on drop.
+    pub fn try_clone(&self) -> io::Result<Master> {
+        unsafe {
+            match libc::fcntl(self.as_raw_fd(), libc::F_DUPFD_CLOEXEC, 0) {
+                -1 => Err(io::Error::last_os_error()),
+                fd => Ok(Master::with_fd(fd)),
+            }
+        }
+    }
+
+    /// Returns the number of bytes read from the terminal by this handle,
+    /// including those kept by `peek`. A clone counts from zero, so each
+    /// handle is accounted apart, while the halves of `split` carry on.
+    pub fn bytes_read(&self) -> u64 {
+        self.bytes_read.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of bytes written to the terminal by this handle,
+    /// including the control characters, counted like `bytes_read`.
+    pub fn bytes_written(&self) -> u64 {
+        self.bytes_written.load(Ordering::Relaxed)
+    }
+
+    /// Resets `bytes_read` and `bytes_written` to zero.
+    pub fn reset_counters(&self) {
+        self.bytes_read.store(0, Ordering::Relaxed);
+        self.bytes_written.store(0, Ordering::Relaxed);
+    }
+
+    /// Returns an iterator over the lines of the output, without
+    /// the trailing `\r\n` of the terminal.
+    pub fn lines(&self) -> Lines<'_> {
+        Lines::new(self)
+    }
+
+    /// The function `buffered` returns a handle which buffers the reads,
+    /// so the data can be peeked at, and still writes to the master.
+    pub fn buffered(self) -> BufferedMaster {
+        BufferedMaster::new(self)
+    }
+
+    /// The function `tee` returns a handle which writes all the data read
+    /// to `log` too, so the session is recorded, and still writes to the
+    /// master.
+    pub fn tee(self, log: File) -> TeeMaster {
+        TeeMaster::new(self, log)
+    }
+
+    /// The function `tee_with_timing` returns a handle like `tee` which
+    /// also records the timing of the reads to `timing`, so the session
+    /// is replayed by `scriptreplay`.
+    pub fn tee_with_timing(self, log: File, timing: File) -> io::Result<TeeMaster> {
+        TeeMaster::with_timing(self, log, timing)
+    }
+
+    /// The function `with_capacity` returns a handle like `buffered`
+    /// which buffers up to `capacity` bytes, so a large output is read
+    /// with fewer calls.
+    pub fn with_capacity(self, capacity: usize) -> BufferedMaster {
+        BufferedMaster::with_capacity(capacity, self)
+    }
+
+    /// Waits at most `timeout` until the master is readable, returns false
+    /// on timeout. It's readable at the end of file too, once the slave is
+    /// closed, so the next read returns it.
+    pub fn wait_readable(&self, timeout: Duration) -> io::Result<bool> {
+        match self.poll(libc::POLLIN, Some(timeout))? {
+            None => Ok(false),
+            Some(revents) => check_revents(revents).and(Ok(true)),
+        }
+    }
+
+    /// Waits at most `timeout` until the master is writable, like after
+    /// a write failed with `io::ErrorKind::WouldBlock`, returns false on
+    /// timeout. It fails with `io::ErrorKind::BrokenPipe` once the slave
+    /// is closed.
+    pub fn wait_writable(&self, timeout: Duration) -> io::Result<bool> {
+        match self.poll(libc::POLLOUT, Some(timeout))? {
+            None => Ok(false),
+            Some(revents) => check_pollout(revents).and(Ok(true)),
+        }
+    }
+
+    /// Waits at most `timeout` for data and reads it into `buf`,
+    /// returns none if nothing was available in time.
+    pub fn read_timeout(&self, buf: &mut [u8], timeout: Duration) -> io::Result<Option<usize>> {
+        match self.poll(libc::POLLIN, Some(timeout))? {
+            None => Ok(None),
+            Some(_) => self.read_fd(buf).map(Some),
+        }
+    }
+
+    /// Reads what is available into `buf` and tells apart the data,
+    /// the end of file, which Linux reports as `EIO` once the child has
+    /// closed its slave, and the lack of data on a non-blocking master.
+    pub fn read_available(&self, buf: &mut [u8]) -> io::Result<ReadOutcome> {
+        match self.read_fd(buf) {
+            Ok(0) => Ok(ReadOutcome::Eof),
+            Ok(len) => Ok(ReadOutcome::Data(len)),
+            Err(ref err) if err.kind() == io::ErrorKind::WouldBlock => Ok(ReadOutcome::WouldBlock),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Waits at most `timeout` for text and returns it, or fails with
+    /// `io::ErrorKind::TimedOut`. A multibyte character split across
+    /// reads is kept for the next call, the invalid bytes are replaced
+    /// with U+FFFD and an empty string is returned at the end of file.
+    pub fn read_text(&self, timeout: Duration) -> io::Result<String> {
+        let deadline = Instant::now() + timeout;
+        let mut partial = self.partial_text.lock().unwrap_or_else(|e| e.into_inner());
+        let mut buf = [0; 4096];
+
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+
+            match self.read_timeout(&mut buf, remaining)? {
+                None => return Err(io::Error::new(io::ErrorKind::TimedOut, "read timed out")),
+                Some(0) => {
+                    let text = String::from_utf8_lossy(&partial).into_owned();
+
+                    partial.clear();
+                    return Ok(text);
+                }
+                Some(len) => {
+                    partial.extend_from_slice(&buf[..len]);
+
+                    let (text, consumed) = decode_utf8(&partial);
+
+                    partial.drain(..consumed);
+                    if !text.is_empty() {
+                        return Ok(text);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the length of the data waiting to be read, without reading it.
+    pub fn bytes_available(&self) -> io::Result<usize> {
+        let mut count: libc::c_int = 0;
+
+        unsafe {
+            match libc::ioctl(self.as_raw_fd(), libc::FIONREAD, &mut count) {
+                -1 => Err(io::Error::last_os_error()),
+                _ => Ok(count as usize + self.peeked_len()),
+            }
+        }
+    }
+
+    /// Returns the length of the data written to the terminal and still
+    /// in its output queue, with `TIOCOUTQ`, like `bytes_available` does
+    /// for the input.
+    ///
+    /// Linux keeps no output queue for the master, which is always empty,
+    /// the input the child hasn't read yet is counted with `FIONREAD` on
+    /// a handle of the slave from `open_slave` instead.
+    pub fn output_queue_len(&self) -> io::Result<usize> {
+        let mut count: libc::c_int = 0;
+
+        unsafe {
+            match libc::ioctl(self.as_raw_fd(), libc::TIOCOUTQ, &mut count) {
+                -1 => Err(io::Error::last_os_error()),
+                _ => Ok(count as usize),
+            }
+        }
+    }
+
+    /// Copies the data waiting to be read into `buf` without consuming it,
+    /// so the next read returns it again, and returns its length. It waits
+    /// for data like a read, then the data of that one read is kept, at
+    /// most 4096 bytes, until the reads consume it. The readiness of `mio`
+    /// and `tokio` doesn't account for the kept data.
+    pub fn peek(&self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut peeked = self.peeked.lock().unwrap_or_else(|e| e.into_inner());
+
+        if peeked.is_empty() {
+            let mut chunk = [0; PEEK_CAPACITY];
+            let len = self.read_raw_fd(&mut chunk)?;
+
+            peeked.extend_from_slice(&chunk[..len]);
+        }
+
+        let len = cmp::min(buf.len(), peeked.len());
+
+        buf[..len].copy_from_slice(&peeked[..len]);
+        Ok(len)
+    }
+
+    /// The function `unread` puts `data` back before the data kept by
+    /// `peek`, so the next read returns it first.
+    fn unread(&self, data: &[u8]) {
+        if !data.is_empty() {
+            let mut peeked = self.peeked.lock().unwrap_or_else(|e| e.into_inner());
+
+            peeked.splice(..0, data.iter().cloned());
+        }
+    }
+
+    /// The function `peeked_len` returns the length of the data kept by `peek`.
+    fn peeked_len(&self) -> usize {
+        self.peeked.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    /// Reads exactly enough to fill `buf` before `timeout` elapses,
+    /// across the short reads of the terminal, or fails with
+    /// `io::ErrorKind::TimedOut`.
+    pub fn read_exact_timeout(&self, buf: &mut [u8], timeout: Duration) -> io::Result<()> {
+        let deadline = Instant::now() + timeout;
+        let mut filled = 0;
+
+        while filled < buf.len() {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+
+            match self.read_timeout(&mut buf[filled..], remaining)? {
+                None => return Err(io::Error::new(io::ErrorKind::TimedOut, "read timed out")),
+                Some(0) => return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "end of file")),
+                Some(len) => filled += len,
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads until `needle` appears before `timeout` elapses and returns
+    /// the bytes read up to and including it, or fails with
+    /// `io::ErrorKind::TimedOut`. It reads in chunks and keeps the bytes
+    /// after `needle` for the next read, like `peek`.
+    pub fn read_until(&self, needle: &[u8], timeout: Duration) -> io::Result<Vec<u8>> {
+        let deadline = Instant::now() + timeout;
+        let mut read = Vec::new();
+        let mut buf = [0; 4096];
+
+        if needle.is_empty() {
+            return Ok(read);
+        }
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            // The needle can start in the bytes of the previous chunk.
+            let start = read.len().saturating_sub(needle.len() - 1);
+
+            match self.read_timeout(&mut buf, remaining)? {
+                None => return Err(io::Error::new(io::ErrorKind::TimedOut, "read timed out")),
+                Some(0) => return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "end of file")),
+                Some(len) => read.extend_from_slice(&buf[..len]),
+            }
+            if let Some(at) = read[start..].windows(needle.len()).position(|w| w == needle) {
+                let end = start + at + needle.len();
+
+                self.unread(&read[end..]);
+                read.truncate(end);
+                return Ok(read);
+            }
+        }
+    }
+
+    /// Writes the whole `buf`, waiting at most `timeout` each time the
+    /// child stops reading and the terminal buffer is full, or fails with
+    /// `io::ErrorKind::TimedOut` when no progress is made in time. The fd
+    /// is non-blocking during the call and restored afterwards, even when
+    /// the write fails. The flag belongs to the file description shared
+    /// with the clones of `try_clone` and the halves of `split`, so the
+    /// call may not run alongside them: a blocking read in another thread
+    /// fails with `io::ErrorKind::WouldBlock` meanwhile.
+    pub fn write_all_timeout(&self, buf: &[u8], timeout: Duration) -> io::Result<()> {
+        let blocking = unsafe {
+            match libc::fcntl(self.as_raw_fd(), libc::F_GETFL) {
+                -1 => return Err(io::Error::last_os_error()),
+                flags => flags & libc::O_NONBLOCK == 0,
+            }
+        };
+
+        if blocking {
+            self.set_nonblocking(true)?;
+        }
+
+        let result = self.write_all_nonblocking(buf, timeout);
+
+        // The error of the write comes first, the one restoring the mode
+        // is only reported after a successful write.
+        if blocking {
+            let restored = self.set_nonblocking(false);
+
+            return result.and(restored);
+        }
+        result
+    }
+
+    /// Writes the whole `buf` like `Write::write_all`, even once the fd is
+    /// non-blocking, as it waits for `POLLOUT` whenever the terminal buffer
+    /// is full instead of failing with `io::ErrorKind::WouldBlock`. The fd
+    /// is left non-blocking, unlike with `write_all_timeout`.
+    pub fn write_blocking_all(&self, mut buf: &[u8]) -> io::Result<()> {
+        while !buf.is_empty() {
+            match self.write_fd(buf) {
+                Ok(len) => buf = &buf[len..],
+                Err(ref err) if err.kind() == io::ErrorKind::WouldBlock => {
+                    if let Some(revents) = self.poll(libc::POLLOUT, None)? {
+                        check_pollout(revents)?;
+                    }
+                }
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(())
+    }
+
+    /// The function `write_all_nonblocking` writes `buf` to the
+    /// non-blocking fd and polls for `POLLOUT` whenever it's full,
+    /// it fails at once when the slave is closed.
+    fn write_all_nonblocking(&self, mut buf: &[u8], timeout: Duration) -> io::Result<()> {
+        while !buf.is_empty() {
+            match self.write_fd(buf) {
+                Ok(len) => buf = &buf[len..],
+                Err(ref err) if err.kind() == io::ErrorKind::WouldBlock => {
+                    match self.poll(libc::POLLOUT, Some(timeout))? {
+                        None => {
+                            return Err(io::Error::new(io::ErrorKind::TimedOut, "write timed out"))
+                        }
+                        Some(revents) => check_pollout(revents)?,
+                    }
+                }
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(())
+    }
+
+    /// The function `read_fd` reads the data kept by `peek` first,
+    /// or else from the fd.
+    fn read_fd(&self, buf: &mut [u8]) -> io::Result<usize> {
+        {
+            let mut peeked = self.peeked.lock().unwrap_or_else(|e| e.into_inner());
+
+            if !peeked.is_empty() {
+                let len = cmp::min(buf.len(), peeked.len());
+
+                buf[..len].copy_from_slice(&peeked[..len]);
+                peeked.drain(..len);
+                return Ok(len);
+            }
+        }
+        self.read_raw_fd(buf)
+    }
+
+    /// The function `read_raw_fd` reads from the fd, it retries on `EINTR`
+    /// and reports the end of file on `EIO`, which Linux returns once the
+    /// slave is closed by the child.
+    fn read_raw_fd(&self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            unsafe {
+                match ffi::read(self.as_raw_fd(), buf.as_mut_ptr(), buf.len()) {
+                    -1 => {
+                        let err = io::Error::last_os_error();
+
+                        match err.raw_os_error() {
+                            Some(libc::EINTR) => continue,
+                            Some(libc::EIO) => return Ok(0),
+                            _ => return Err(err),
+                        }
+                    }
+                    len => {
+                        self.bytes_read.fetch_add(len as u64, Ordering::Relaxed);
+                        return Ok(len as usize);
+                    }
+                }
+            }
+        }
+    }
+
+    /// The function `write_fd` writes to the fd, it retries on `EINTR` and
+    /// after short writes, so the whole buffer is written unless an error
+    /// occurs, the length written so far is then returned if any.
+    fn write_fd(&self, buf: &[u8]) -> io::Result<usize> {
+        let mut written = 0;
+
+        self.check_writable()?;
+        while written < buf.len() {
+            let rest = &buf[written..];
+
+            match unsafe { ffi::write(self.as_raw_fd(), rest.as_ptr(), rest.len()) } {
+                -1 => {
+                    let err = io::Error::last_os_error();
+
+                    match err.raw_os_error() {
+                        Some(libc::EINTR) => continue,
+                        _ if written > 0 => break,
+                        _ => return Err(err),
+                    }
+                }
+                len => written += len as usize,
+            }
+        }
+        self.bytes_written.fetch_add(written as u64, Ordering::Relaxed);
+        Ok(written)
+    }
+
+    /// The function `read_vectored_fd` reads from the fd into `bufs` in
+    /// one `readv`, with the same error handling as `read_fd`. The data
+    /// kept by `peek` is read into the first buffer instead.
+    fn read_vectored_fd(&self, bufs: &mut [io::IoSliceMut]) -> io::Result<usize> {
+        if self.peeked_len() > 0 {
+            return match bufs.iter_mut().find(|buf| !buf.is_empty()) {
+                Some(buf) => self.read_fd(buf),
+                None => Ok(0),
+            };
+        }
+
+        // `IoSliceMut` is guaranteed to be ABI compatible with `iovec`.
+        let iovecs = bufs.as_ptr() as *const libc::iovec;
+        let count = cmp::min(bufs.len(), MAX_IOVECS) as libc::c_int;
+
+        loop {
+            match unsafe { libc::readv(self.as_raw_fd(), iovecs, count) } {
+                -1 => {
+                    let err = io::Error::last_os_error();
+
+                    match err.raw_os_error() {
+                        Some(libc::EINTR) => continue,
+                        Some(libc::EIO) => return Ok(0),
+                        _ => return Err(err),
+                    }
+                }
+                len => {
+                    self.bytes_read.fetch_add(len as u64, Ordering::Relaxed);
+                    return Ok(len as usize);
+                }
+            }
+        }
+    }
+
+    /// The function `check_writable` fails once `close_write` is called.
+    fn check_writable(&self) -> io::Result<()> {
+        if self.write_closed.load(Ordering::SeqCst) {
+            Err(io::Error::new(io::ErrorKind::BrokenPipe, "the write side is closed"))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// The function `write_vectored_fd` writes `bufs` to the fd with
+    /// `writev`, it advances past the short writes like `write_fd`.
+    fn write_vectored_fd(&self, bufs: &[io::IoSlice]) -> io::Result<usize> {
+        let mut slices = bufs.to_vec();
+        let mut rest = &mut slices[..];
+        let mut written = 0;
+
+        self.check_writable()?;
+        // Skips the empty buffers, so nothing is written without data.
+        io::IoSlice::advance_slices(&mut rest, 0);
+        while !rest.is_empty() {
+            // `IoSlice` is guaranteed to be ABI compatible with `iovec`.
+            let iovecs = rest.as_ptr() as *const libc::iovec;
+            let count = cmp::min(rest.len(), MAX_IOVECS) as libc::c_int;
+
+            match unsafe { libc::writev(self.as_raw_fd(), iovecs, count) } {
+                -1 => {
+                    let err = io::Error::last_os_error();
+
+                    match err.raw_os_error() {
+                        Some(libc::EINTR) => continue,
+                        _ if written > 0 => break,
+                        _ => return Err(err),
+                    }
+                }
+                len => {
+                    written += len as usize;
+                    io::IoSlice::advance_slices(&mut rest, len as usize);
+                }
+            }
+        }
+        self.bytes_written.fetch_add(written as u64, Ordering::Relaxed);
+        Ok(written)
+    }
+
+    /// The function `poll` waits at most `timeout`, or without a limit
+    /// for none, for one of `events` on the fd and returns the received
+    /// events, or none on timeout. It retries with the remaining time when
+    /// interrupted by a signal, and the data kept by `peek` is readable
+    /// at once.
+    fn poll(&self,
+            events: libc::c_short,
+            timeout: Option<Duration>)
+            -> io::Result<Option<libc::c_short>> {
+        if events & libc::POLLIN != 0 && self.peeked_len() > 0 {
+            return Ok(Some(libc::POLLIN));
+        }
+
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        let mut fds = libc::pollfd {
+            fd: self.as_raw_fd(),
+            events,
+            revents: 0,
+        };
+
+        loop {
+            let wait = match deadline {
+                Some(deadline) => millis(deadline.saturating_duration_since(Instant::now())),
+                None => -1,
+            };
+
+            match unsafe { libc::poll(&mut fds, 1, wait) } {
+                -1 => {
+                    let err = io::Error::last_os_error();
+
+                    if err.raw_os_error() != Some(libc::EINTR) {
+                        return Err(err);
+                    }
+                }
+                0 => return Ok(None),
+                _ => return Ok(Some(fds.revents)),
+            }
+        }
+    }
+
+    /// Toggles the `ECHO` flag of the terminal. It's a setting of the line
+    /// discipline shared with the child, which sees it too, not only the master.
+    pub fn set_echo(&self, on: bool) -> io::Result<()> {
+        let mut termios = self.tcgetattr()?;
+
+        if on {
+            termios.c_lflag |= libc::ECHO;
+        } else {
+            termios.c_lflag &= !libc::ECHO;
+        }
+        self.set_termios(&termios)
+    }
+
+    /// Toggles the `OPOST` flag of the terminal, without it the output of
+    /// the child is passed as is, like `\n` without the added `\r`, so a
+    /// session is recorded byte for byte. It doesn't depend on the canonical
+    /// mode, `set_noncanonical` leaves it alone while `set_raw` clears it.
+    pub fn set_output_processing(&self, on: bool) -> io::Result<()> {
+        let mut termios = self.tcgetattr()?;
+
+        if on {
+            termios.c_oflag |= libc::OPOST;
+        } else {
+            termios.c_oflag &= !libc::OPOST;
+        }
+        self.set_termios(&termios)
+    }
+
+    /// Returns whether the `OPOST` flag of the terminal is set,
+    /// so the output of the child is processed.
+    pub fn output_processing(&self) -> io::Result<bool> {
+        self.tcgetattr().map(|termios| termios.c_oflag & libc::OPOST != 0)
+    }
+
+    /// Toggles the packet mode of the terminal with `TIOCPKT`, like a
+    /// remote login server does. Each read then starts with a control
+    /// byte, split off with `PacketControl::parse`: `TIOCPKT_DATA` before
+    /// the output of the child, or the flushes and the flow control of the
+    /// terminal alone, so the reads aren't the plain output anymore.
+    /// It is left out of NetBSD and Android, whose `libc` has no `TIOCPKT`.
+    #[cfg(any(target_os = "linux",
+              target_os = "macos",
+              target_os = "ios",
+              target_os = "freebsd",
+              target_os = "dragonfly"))]
+    pub fn set_packet_mode(&self, on: bool) -> io::Result<()> {
+        let on: libc::c_int = if on { 1 } else { 0 };
+
+        unsafe {
+            match libc::ioctl(self.as_raw_fd(), libc::TIOCPKT, &on) {
+                -1 => Err(io::Error::last_os_error()),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    /// Sets the control character `index` of the terminal, like
+    /// `libc::VINTR`, to `value`. It fails with `io::ErrorKind::InvalidInput`
+    /// for an index outside of `c_cc`.
+    pub fn set_cc(&self, index: usize, value: u8) -> io::Result<()> {
+        let mut termios = self.tcgetattr()?;
+
+        match termios.c_cc.get_mut(index) {
+            None => Err(io::Error::new(io::ErrorKind::InvalidInput, "no such control character")),
+            Some(cc) => {
+                *cc = value;
+                self.set_termios(&termios)
+            }
+        }
+    }
+
+    /// Turns the line by line input off, a read of the slave then returns
+    /// once `vmin` bytes are available or `vtime` tenths of a second have
+    /// elapsed, like `1` and `0` to read each byte at once.
+    pub fn set_noncanonical(&self, vmin: u8, vtime: u8) -> io::Result<()> {
+        let mut termios = self.tcgetattr()?;
+
+        termios.c_lflag &= !libc::ICANON;
+        termios.c_cc[libc::VMIN] = vmin;
+        termios.c_cc[libc::VTIME] = vtime;
+        self.set_termios(&termios)
+    }
+
+    /// Discards the data pending in the `queue` of the terminal.
+    pub fn flush_io(&self, queue: FlushQueue) -> io::Result<()> {
+        unsafe {
+            match libc::tcflush(self.as_raw_fd(), queue.as_raw()) {
+                -1 => Err(io::Error::last_os_error()),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    /// Writes the `VEOF` character of the terminal, so a child reading
+    /// its stdin in canonical mode gets the end of file at the start of a line.
+    pub fn send_eof(&self) -> io::Result<()> {
+        self.send_cc(libc::VEOF)
+    }
+
+    /// Half-closes the master: since a PTY doesn't support `shutdown(2)`,
+    /// the `VEOF` character is sent instead, so the child gets the end
+    /// of file if it reads in canonical mode at the start of a line, and
+    /// then the writes through this master fail with
+    /// `io::ErrorKind::BrokenPipe`. The reads and the duplicates of the
+    /// fd aren't affected.
+    pub fn close_write(&self) -> io::Result<()> {
+        if !self.write_closed.load(Ordering::SeqCst) {
+            self.send_eof()?;
+            self.write_closed.store(true, Ordering::SeqCst);
+        }
+        Ok(())
+    }
+
+    /// Writes the `VINTR`, `VQUIT` or `VSUSP` character of the terminal, so
+    /// the line discipline raises `SIGINT`, `SIGQUIT` or `SIGTSTP` in the child.
+    pub fn send_signal_char(&self, signal: libc::c_int) -> io::Result<()> {
+        match signal {
+            libc::SIGINT => self.send_cc(libc::VINTR),
+            libc::SIGQUIT => self.send_cc(libc::VQUIT),
+            libc::SIGTSTP => self.send_cc(libc::VSUSP),
+            _ => Err(io::Error::new(io::ErrorKind::InvalidInput, "no character for the signal")),
+        }
+    }
+
+    /// Writes the control character typed with `Ctrl` and `c`, like `0x03`
+    /// for `'c'`, whatever the case of the letter and `0x7f` for `'?'`.
+    /// It fails with `io::ErrorKind::InvalidInput` for another character
+    /// than a letter or one of `@[\]^_?`.
+    pub fn send_control(&self, c: char) -> io::Result<()> {
+        let byte = match c.to_ascii_uppercase() {
+            '?' => 0x7f,
+            c @ '@'..='_' => c as u8 & 0x1f,
+            _ => return Err(io::Error::new(io::ErrorKind::InvalidInput, "no control character")),
+        };
+
+        self.write_fd(&[byte]).map(|_| ())
+    }
+
+    /// The function `send_cc` writes the control character `index` of the terminal.
+    fn send_cc(&self, index: usize) -> io::Result<()> {
+        let termios = self.tcgetattr()?;
+
+        self.write_fd(&[termios.c_cc[index]]).map(|_| ())
+    }
+
+    /// The function `tcgetattr` returns the current terminal settings.
+    fn tcgetattr(&self) -> io::Result<libc::termios> {
+        unsafe {
+            let mut termios: libc::termios = mem::zeroed();
+
+            match libc::tcgetattr(self.as_raw_fd(), &mut termios) {
+                -1 => Err(io::Error::last_os_error()),
+                _ => Ok(termios),
+            }
+        }
+    }
+}
+
+/// The function `check_revents` fails for the error events of `poll`,
+/// which are reported whatever the events waited for.
+fn check_revents(revents: libc::c_short) -> io::Result<()> {
+    if revents & libc::POLLNVAL != 0 {
+        Err(io::Error::from_raw_os_error(libc::EBADF))
+    } else if revents & libc::POLLERR != 0 {
+        Err(io::Error::other("the terminal has an error pending"))
+    } else {
+        Ok(())
+    }
+}
+
+/// The function `check_pollout` fails like `check_revents`, and with
+/// `io::ErrorKind::BrokenPipe` once the slave is closed.
+fn check_pollout(revents: libc::c_short) -> io::Result<()> {
+    if revents & libc::POLLHUP != 0 {
+        Err(io::Error::new(io::ErrorKind::BrokenPipe, "the slave is closed"))
+    } else {
+        check_revents(revents)
+    }
+}
+
+/// The function `decode_utf8` decodes `bytes` up to a trailing incomplete
+/// character, with U+FFFD for the invalid bytes, and returns the text
+/// and the length decoded.
+fn decode_utf8(bytes: &[u8]) -> (String, usize) {
+    let mut text = String::new();
+    let mut rest = bytes;
+
+    loop {
+        match str::from_utf8(rest) {
+            Ok(valid) => {
+                text.push_str(valid);
+                return (text, bytes.len());
+            }
+            Err(err) => {
+                let (valid, after) = rest.split_at(err.valid_up_to());
+
+                text.push_str(str::from_utf8(valid).unwrap_or_default());
+                match err.error_len() {
+                    Some(len) => {
+                        text.push('\u{FFFD}');
+                        rest = &after[len..];
+                    }
+                    None => return (text, bytes.len() - after.len()),
+                }
+            }
+        }
+    }
+}
+
+/// The function `millis` rounds up `duration` to a `poll` timeout.
+fn millis(duration: Duration) -> libc::c_int {
+    let millis = duration.as_secs()
+        .saturating_mul(1000)
+        .saturating_add(u64::from(duration.subsec_nanos()).div_ceil(1_000_000));
+
+    cmp::min(millis, libc::c_int::MAX as u64) as libc::c_int
 }
 
 impl Descriptor for Master {}
@@ -63,28 +1206,68 @@ impl AsRawFd for Master {
     }
 }
 
+impl FromRawFd for Master {
+    /// The constructor function `from_raw_fd` wraps a fd already opened
+    /// as a PTY master elsewhere.
+    unsafe fn from_raw_fd(fd: RawFd) -> Self {
+        Master::with_fd(fd)
+    }
+}
+
+impl IntoRawFd for Master {
+    /// The function `into_raw_fd` gives up the fd without closing it,
+    /// nor hanging up the child.
+    fn into_raw_fd(mut self) -> RawFd {
+        let fd = self.pty;
+
+        self.cancel_hangup();
+        // The fd is given up, `drop` releases the rest.
+        self.pty = -1;
+        fd
+    }
+}
+
 impl io::Read for Master {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
-        unsafe {
-            match ffi::read(self.as_raw_fd(), buf.as_mut_ptr(), buf.len()) {
-                -1 => Ok(0),
-                len => Ok(len as usize),
-            }
-        }
+        self.read_fd(buf)
+    }
+
+    fn read_vectored(&mut self, bufs: &mut [io::IoSliceMut]) -> io::Result<usize> {
+        self.read_vectored_fd(bufs)
+    }
+}
+
+impl io::Read for &Master {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.read_fd(buf)
+    }
+
+    fn read_vectored(&mut self, bufs: &mut [io::IoSliceMut]) -> io::Result<usize> {
+        self.read_vectored_fd(bufs)
     }
 }
 
 impl io::Write for Master {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        unsafe {
-            match ffi::write(self.as_raw_fd(), buf.as_ptr(), buf.len()) {
-                -1 => Err(io::Error::last_os_error()),
-                ret => Ok(ret as usize),
-            }
-        }
+        self.write_fd(buf)
+    }
+
+    fn write_vectored(&mut self, bufs: &[io::IoSlice]) -> io::Result<usize> {
+        self.write_vectored_fd(bufs)
     }
 
     fn flush(&mut self) -> io::Result<()> {
         Ok(())
     }
 }
+
+impl Drop for Master {
+    /// The destructor function `drop` hangs up the child if asked
+    /// and closes the fd at best effort.
+    fn drop(&mut self) {
+        self.hang_up();
+        if self.pty != -1 {
+            let _ = self.close_fd();
+        }
+    }
+}
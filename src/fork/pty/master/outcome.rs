@@ -0,0 +1,11 @@
+/// The enum `ReadOutcome` tells apart what `Master::read_available`
+/// found on the terminal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReadOutcome {
+    /// The length read into the buffer.
+    Data(usize),
+    /// The child has closed its slave.
+    Eof,
+    /// Nothing to read yet on a non-blocking master.
+    WouldBlock,
+}
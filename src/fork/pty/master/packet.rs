@@ -0,0 +1,69 @@
+/// The struct `PacketControl` is the control byte which starts each read
+/// of a master in packet mode, set by `Master::set_packet_mode`, either
+/// `TIOCPKT_DATA` before the output of the child or else the changes of
+/// the terminal state alone.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PacketControl(u8);
+
+/// The bits of the control byte, the same on Linux and the BSDs.
+const TIOCPKT_FLUSHREAD: u8 = 0x01;
+const TIOCPKT_FLUSHWRITE: u8 = 0x02;
+const TIOCPKT_STOP: u8 = 0x04;
+const TIOCPKT_START: u8 = 0x08;
+const TIOCPKT_NOSTOP: u8 = 0x10;
+const TIOCPKT_DOSTOP: u8 = 0x20;
+
+impl PacketControl {
+    /// The function `parse` splits a read `buf` into its control byte and
+    /// the data which follows, empty for a status, or none if it's empty.
+    pub fn parse(buf: &[u8]) -> Option<(Self, &[u8])> {
+        buf.split_first().map(|(&control, data)| (PacketControl(control), data))
+    }
+
+    /// The constructor function `from_raw` wraps the control byte `control`.
+    pub fn from_raw(control: u8) -> Self {
+        PacketControl(control)
+    }
+
+    /// The accessor function `as_raw` returns the control byte.
+    pub fn as_raw(&self) -> u8 {
+        self.0
+    }
+
+    /// Returns whether the output of the child follows, `TIOCPKT_DATA`.
+    pub fn is_data(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns whether the input queue of the terminal has been flushed.
+    pub fn flush_read(&self) -> bool {
+        self.0 & TIOCPKT_FLUSHREAD != 0
+    }
+
+    /// Returns whether the output queue of the terminal has been flushed.
+    pub fn flush_write(&self) -> bool {
+        self.0 & TIOCPKT_FLUSHWRITE != 0
+    }
+
+    /// Returns whether the output has been stopped, like with `^S`.
+    pub fn stop(&self) -> bool {
+        self.0 & TIOCPKT_STOP != 0
+    }
+
+    /// Returns whether the output has been restarted, like with `^Q`.
+    pub fn start(&self) -> bool {
+        self.0 & TIOCPKT_START != 0
+    }
+
+    /// Returns whether the `IXON` flow control has been turned off,
+    /// so the client handles `^S` and `^Q` as input.
+    pub fn no_stop(&self) -> bool {
+        self.0 & TIOCPKT_NOSTOP != 0
+    }
+
+    /// Returns whether the `IXON` flow control has been turned on with
+    /// the usual `^S` and `^Q`, so the client can handle them locally.
+    pub fn do_stop(&self) -> bool {
+        self.0 & TIOCPKT_DOSTOP != 0
+    }
+}
@@ -0,0 +1,24 @@
+use ::libc;
+
+/// The enum `FlushQueue` selects the terminal queues discarded
+/// by `Master::flush_io`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FlushQueue {
+    /// The data received but not read.
+    Input,
+    /// The data written but not transmitted.
+    Output,
+    /// Both the input and the output.
+    Both,
+}
+
+impl FlushQueue {
+    /// The accessor function `as_raw` returns the `tcflush` selector.
+    pub fn as_raw(&self) -> libc::c_int {
+        match *self {
+            FlushQueue::Input => libc::TCIFLUSH,
+            FlushQueue::Output => libc::TCOFLUSH,
+            FlushQueue::Both => libc::TCIOFLUSH,
+        }
+    }
+}
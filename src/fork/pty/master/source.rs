@@ -0,0 +1,31 @@
+use ::mio::{Interest, Registry, Token};
+use ::mio::event::Source;
+use ::mio::unix::SourceFd;
+
+use super::Master;
+use std::io;
+use std::os::unix::io::AsRawFd;
+
+/// The master registers its fd with a `mio::Poll`, which expects
+/// the fd to be set non-blocking by `Master::set_nonblocking` first.
+impl Source for Master {
+    fn register(&mut self,
+                registry: &Registry,
+                token: Token,
+                interests: Interest)
+                -> io::Result<()> {
+        SourceFd(&self.as_raw_fd()).register(registry, token, interests)
+    }
+
+    fn reregister(&mut self,
+                  registry: &Registry,
+                  token: Token,
+                  interests: Interest)
+                  -> io::Result<()> {
+        SourceFd(&self.as_raw_fd()).reregister(registry, token, interests)
+    }
+
+    fn deregister(&mut self, registry: &Registry) -> io::Result<()> {
+        SourceFd(&self.as_raw_fd()).deregister(registry)
+    }
+}
@@ -0,0 +1,82 @@
+use super::Master;
+use std::io;
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::time::Duration;
+
+/// The struct `MasterReader` is the read half of a `Master`,
+/// returned by `Master::split`.
+#[derive(Debug)]
+pub struct MasterReader {
+    master: Master,
+}
+
+/// The struct `MasterWriter` is the write half of a `Master`,
+/// returned by `Master::split`.
+#[derive(Debug)]
+pub struct MasterWriter {
+    master: Master,
+}
+
+impl MasterReader {
+    /// The constructor function `new` takes ownership of the duplicated `master`.
+    pub fn new(master: Master) -> Self {
+        MasterReader { master }
+    }
+
+    /// Returns the number of bytes read, like `Master::bytes_read`.
+    pub fn bytes_read(&self) -> u64 {
+        self.master.bytes_read()
+    }
+
+    /// Waits at most `timeout` for text and returns it, like `Master::read_text`.
+    pub fn read_text(&self, timeout: Duration) -> io::Result<String> {
+        self.master.read_text(timeout)
+    }
+}
+
+impl MasterWriter {
+    /// The constructor function `new` takes ownership of the duplicated `master`.
+    pub fn new(master: Master) -> Self {
+        MasterWriter { master }
+    }
+
+    /// Returns the number of bytes written, like `Master::bytes_written`.
+    pub fn bytes_written(&self) -> u64 {
+        self.master.bytes_written()
+    }
+
+    /// Half-closes the writer, like `Master::close_write`.
+    pub fn close_write(&self) -> io::Result<()> {
+        self.master.close_write()
+    }
+}
+
+impl AsRawFd for MasterReader {
+    /// The accessor function `as_raw_fd` returns the fd.
+    fn as_raw_fd(&self) -> RawFd {
+        self.master.as_raw_fd()
+    }
+}
+
+impl AsRawFd for MasterWriter {
+    /// The accessor function `as_raw_fd` returns the fd.
+    fn as_raw_fd(&self) -> RawFd {
+        self.master.as_raw_fd()
+    }
+}
+
+impl io::Read for MasterReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.master.read(buf)
+    }
+}
+
+impl io::Write for MasterWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.master.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.master.flush()
+    }
+}
@@ -0,0 +1,112 @@
+use super::Master;
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::time::Instant;
+
+/// The struct `TeeMaster` records a session, each read from the `Master`
+/// is written to a log before it's returned, returned by `Master::tee`.
+///
+/// With the timing file of `Master::tee_with_timing`, the log and the
+/// timing are replayed by `scriptreplay --timing=<timing> <log>`.
+#[derive(Debug)]
+pub struct TeeMaster {
+    master: Master,
+    log: File,
+    timing: Option<(File, Instant)>,
+    input: Option<File>,
+}
+
+impl TeeMaster {
+    /// The constructor function `new` takes ownership of `master`
+    /// and logs its output to `log`.
+    pub fn new(master: Master, log: File) -> Self {
+        TeeMaster {
+            master,
+            log,
+            timing: None,
+            input: None,
+        }
+    }
+
+    /// The constructor function `with_timing` logs like `new`, and each
+    /// read to `timing` as a `delay bytecount` line, like `script -t`.
+    /// The log starts with a header line, which `scriptreplay` skips.
+    pub fn with_timing(master: Master, mut log: File, timing: File) -> io::Result<Self> {
+        log.write_all(b"Script started\n")?;
+        Ok(TeeMaster {
+            master,
+            log,
+            timing: Some((timing, Instant::now())),
+            input: None,
+        })
+    }
+
+    /// Logs the writes to the master to `input` too, like `script -I`.
+    pub fn log_input(&mut self, input: File) -> &mut Self {
+        self.input = Some(input);
+        self
+    }
+
+    /// The accessor function `get_ref` returns the master.
+    pub fn get_ref(&self) -> &Master {
+        &self.master
+    }
+
+    /// The function `into_inner` returns the master, the logs are closed.
+    pub fn into_inner(self) -> Master {
+        self.master
+    }
+}
+
+impl AsRawFd for TeeMaster {
+    /// The accessor function `as_raw_fd` returns the fd.
+    fn as_raw_fd(&self) -> RawFd {
+        self.master.as_raw_fd()
+    }
+}
+
+impl Read for TeeMaster {
+    /// The function `read` reads from the master and logs the data before
+    /// returning it, it fails with the error of the log, the data is lost.
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = self.master.read(buf)?;
+
+        if len > 0 {
+            self.log.write_all(&buf[..len])?;
+            if let Some((ref mut timing, ref mut last)) = self.timing {
+                let now = Instant::now();
+                let delay = now.duration_since(*last);
+
+                *last = now;
+                writeln!(timing, "{}.{:06} {}", delay.as_secs(), delay.subsec_micros(), len)?;
+            }
+        }
+        Ok(len)
+    }
+}
+
+impl Write for TeeMaster {
+    /// The function `write` writes to the master, then logs
+    /// the data written to the input log if any.
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let len = self.master.write(buf)?;
+
+        if let Some(ref mut input) = self.input {
+            input.write_all(&buf[..len])?;
+        }
+        Ok(len)
+    }
+
+    /// The function `flush` flushes the logs.
+    fn flush(&mut self) -> io::Result<()> {
+        self.log.flush()?;
+        if let Some((ref mut timing, _)) = self.timing {
+            timing.flush()?;
+        }
+        if let Some(ref mut input) = self.input {
+            input.flush()?;
+        }
+        Ok(())
+    }
+}
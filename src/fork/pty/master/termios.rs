@@ -0,0 +1,116 @@
+use ::libc;
+use std::fmt;
+use std::mem;
+
+/// The struct `Termios` is a copy of the terminal settings,
+/// returned by `Master::termios` and applied by `Master::apply`.
+#[derive(Clone, Copy)]
+pub struct Termios {
+    raw: libc::termios,
+}
+
+impl Termios {
+    /// The constructor function `from_raw` wraps the settings `raw`.
+    pub fn from_raw(raw: libc::termios) -> Self {
+        Termios { raw }
+    }
+
+    /// The constructor function `sane` returns the usual settings of a
+    /// terminal, like `stty sane` sets: the input is echoed and read line
+    /// by line, the control characters are the usual ones and the output
+    /// translates `\n` to `\r\n`, at 38400 bauds with 8 bits characters.
+    pub fn sane() -> Self {
+        let mut raw: libc::termios = unsafe { mem::zeroed() };
+
+        raw.c_iflag = libc::BRKINT | libc::ICRNL | libc::IMAXBEL | libc::IXON;
+        raw.c_oflag = libc::OPOST | libc::ONLCR;
+        raw.c_cflag = libc::CS8 | libc::CREAD | libc::HUPCL;
+        raw.c_lflag = libc::ISIG | libc::ICANON | libc::IEXTEN | libc::ECHO | libc::ECHOE |
+                      libc::ECHOK | libc::ECHOCTL | libc::ECHOKE;
+        for cc in raw.c_cc.iter_mut() {
+            *cc = libc::_POSIX_VDISABLE;
+        }
+        for &(index, value) in &[(libc::VINTR, 0x03),
+                                 (libc::VQUIT, 0x1c),
+                                 (libc::VERASE, 0x7f),
+                                 (libc::VKILL, 0x15),
+                                 (libc::VEOF, 0x04),
+                                 (libc::VSTART, 0x11),
+                                 (libc::VSTOP, 0x13),
+                                 (libc::VSUSP, 0x1a),
+                                 (libc::VREPRINT, 0x12),
+                                 (libc::VWERASE, 0x17),
+                                 (libc::VLNEXT, 0x16),
+                                 (libc::VDISCARD, 0x0f),
+                                 (libc::VMIN, 1),
+                                 (libc::VTIME, 0)] {
+            raw.c_cc[index] = value;
+        }
+        unsafe {
+            libc::cfsetispeed(&mut raw, libc::B38400);
+            libc::cfsetospeed(&mut raw, libc::B38400);
+        }
+        Termios { raw }
+    }
+
+    /// The accessor function `as_raw` returns the settings for `libc`.
+    pub fn as_raw(&self) -> &libc::termios {
+        &self.raw
+    }
+
+    /// Returns whether the input is echoed back.
+    pub fn echo(&self) -> bool {
+        self.local_flag(libc::ECHO)
+    }
+
+    /// Returns whether the input is read line by line.
+    pub fn icanon(&self) -> bool {
+        self.local_flag(libc::ICANON)
+    }
+
+    /// Returns whether the control characters like `^C` raise signals.
+    pub fn isig(&self) -> bool {
+        self.local_flag(libc::ISIG)
+    }
+
+    /// Returns a copy with the echo turned on or off.
+    pub fn with_echo(self, on: bool) -> Self {
+        self.with_local_flag(libc::ECHO, on)
+    }
+
+    /// Returns a copy with the line by line input turned on or off.
+    pub fn with_icanon(self, on: bool) -> Self {
+        self.with_local_flag(libc::ICANON, on)
+    }
+
+    /// Returns a copy with the signals of the control characters
+    /// turned on or off.
+    pub fn with_isig(self, on: bool) -> Self {
+        self.with_local_flag(libc::ISIG, on)
+    }
+
+    /// The function `local_flag` returns whether `flag` is set in `c_lflag`.
+    fn local_flag(&self, flag: libc::tcflag_t) -> bool {
+        self.raw.c_lflag & flag != 0
+    }
+
+    /// The function `with_local_flag` sets or clears `flag` in `c_lflag`.
+    fn with_local_flag(mut self, flag: libc::tcflag_t, on: bool) -> Self {
+        if on {
+            self.raw.c_lflag |= flag;
+        } else {
+            self.raw.c_lflag &= !flag;
+        }
+        self
+    }
+}
+
+impl fmt::Debug for Termios {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Termios")
+            .field("echo", &self.echo())
+            .field("icanon", &self.icanon())
+            .field("isig", &self.isig())
+            .finish()
+    }
+}
@@ -1,5 +1,105 @@
 mod master;
+mod pool;
 mod slave;
 
-pub use self::master::{Master, MasterError};
+use ::libc;
+
+#[cfg(feature = "tokio")]
+pub use self::master::AsyncMaster;
+pub use self::master::{Baud, BufferedMaster, FlushQueue, Lines, Master, MasterError,
+                       MasterReader, MasterWriter, PacketControl, ReadOutcome, TeeMaster,
+                       Termios};
+pub use self::pool::{PooledPty, PtyPool};
 pub use self::slave::{Slave, SlaveError};
+use std::ffi::CString;
+use std::io;
+
+/// The function `openpty` allocates a pair of master and slave for a
+/// default PTMX's path without forking, so the caller can decide what
+/// to do with the slave.
+#[cfg(not(any(target_os = "macos",
+              target_os = "ios",
+              target_os = "freebsd",
+              target_os = "dragonfly",
+              target_os = "netbsd",
+              target_os = "openbsd")))]
+pub fn openpty() -> io::Result<(Master, Slave)> {
+    let path = CString::new(::default_ptmx()).ok().unwrap_or_default();
+    let master = Master::new(path.as_ptr())?;
+
+    master.grantpt().and(master.unlockpt())?;
+
+    let slave = master.open_slave()?;
+
+    Ok((master, slave))
+}
+
+/// The function `openpty` allocates a pair of master and slave with
+/// `openpty(3)` of the BSDs without forking, so the caller can decide
+/// what to do with the slave. It doesn't use the default PTMX's path,
+/// so `PTY_PTMX_PATH` is ignored. Both descriptors get `FD_CLOEXEC` right
+/// after `openpty`, which can't set it, a concurrent fork can still
+/// inherit them in between.
+#[cfg(any(target_os = "macos",
+          target_os = "ios",
+          target_os = "freebsd",
+          target_os = "dragonfly",
+          target_os = "netbsd",
+          target_os = "openbsd"))]
+pub fn openpty() -> io::Result<(Master, Slave)> {
+    use std::os::unix::io::{AsRawFd, FromRawFd};
+    use std::ptr;
+
+    let mut master = -1;
+    let mut slave = -1;
+
+    unsafe {
+        if libc::openpty(&mut master,
+                         &mut slave,
+                         ptr::null_mut(),
+                         ptr::null_mut(),
+                         ptr::null_mut()) == -1 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+
+    let master = unsafe { Master::from_raw_fd(master) };
+    let slave = Slave::with_fd(slave, None);
+
+    for fd in &[master.as_raw_fd(), slave.as_raw_fd()] {
+        if unsafe { libc::fcntl(*fd, libc::F_SETFD, libc::FD_CLOEXEC) } == -1 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+    Ok((master, slave))
+}
+
+/// The function `release_controlling_terminal` detaches the calling
+/// process from its controlling terminal, it's a no-op without one.
+/// A session leader detaches its whole session and sends `SIGHUP`
+/// to its foreground process group, which it can be part of.
+pub fn release_controlling_terminal() -> io::Result<()> {
+    let path = CString::new("/dev/tty").ok().unwrap_or_default();
+
+    unsafe {
+        match libc::open(path.as_ptr(), libc::O_RDWR | libc::O_NOCTTY | libc::O_CLOEXEC) {
+            -1 => {
+                let err = io::Error::last_os_error();
+
+                match err.raw_os_error() {
+                    Some(libc::ENXIO) => Ok(()),
+                    _ => Err(err),
+                }
+            }
+            fd => {
+                let result = match libc::ioctl(fd, libc::TIOCNOTTY) {
+                    -1 => Err(io::Error::last_os_error()),
+                    _ => Ok(()),
+                };
+
+                libc::close(fd);
+                result
+            }
+        }
+    }
+}
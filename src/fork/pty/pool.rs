@@ -0,0 +1,146 @@
+use ::libc;
+
+use super::{openpty, FlushQueue, Master, Slave, Termios};
+use std::io;
+use std::os::unix::io::AsRawFd;
+use std::sync::Mutex;
+
+/// The struct `PtyPool` keeps pairs of master and slave allocated,
+/// so many short-lived sessions don't pay for `posix_openpt`,
+/// `grantpt` and `unlockpt` each time.
+#[derive(Debug)]
+pub struct PtyPool {
+    ptys: Mutex<Vec<Pty>>,
+}
+
+/// The struct `PooledPty` is a pair of master and slave acquired from
+/// a `PtyPool`, which it returns to on drop.
+///
+/// While the pool keeps its slave open, a read of the master doesn't get
+/// the end of file once the child exits, `take_slave` hands it over so
+/// the child holds the last one.
+#[derive(Debug)]
+pub struct PooledPty<'a> {
+    pool: &'a PtyPool,
+    pty: Option<Pty>,
+}
+
+/// The struct `Pty` is a pair with its settings from the allocation.
+#[derive(Debug)]
+struct Pty {
+    master: Master,
+    slave: Option<Slave>,
+    termios: Termios,
+    window_size: (u16, u16),
+}
+
+impl PtyPool {
+    /// The constructor function `new` allocates `size` pairs.
+    pub fn new(size: usize) -> io::Result<Self> {
+        let mut ptys = Vec::with_capacity(size);
+
+        for _ in 0..size {
+            ptys.push(Pty::new()?);
+        }
+        Ok(PtyPool { ptys: Mutex::new(ptys) })
+    }
+
+    /// Returns the count of the pairs ready to be acquired.
+    pub fn available(&self) -> usize {
+        self.ptys.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    /// Acquires a pair from the pool, or allocates a new one if the
+    /// pool is empty.
+    pub fn acquire(&self) -> io::Result<PooledPty<'_>> {
+        let pty = match self.ptys.lock().unwrap_or_else(|e| e.into_inner()).pop() {
+            Some(pty) => pty,
+            None => Pty::new()?,
+        };
+
+        Ok(PooledPty {
+            pool: self,
+            pty: Some(pty),
+        })
+    }
+}
+
+impl<'a> PooledPty<'a> {
+    /// The accessor function `master` returns the master.
+    pub fn master(&self) -> &Master {
+        &self.pty().master
+    }
+
+    /// The accessor function `slave` returns the slave kept by the pool,
+    /// or none once it's taken.
+    pub fn slave(&self) -> Option<&Slave> {
+        self.pty().slave.as_ref()
+    }
+
+    /// Takes the slave kept by the pool, or opens another once it's taken,
+    /// to hand it over to a child, like with `pre_exec` and
+    /// `make_controlling_terminal`. The master then reads the end of file
+    /// when the child exits, the pool reopens its slave on drop.
+    pub fn take_slave(&mut self) -> io::Result<Slave> {
+        match self.pty_mut().slave.take() {
+            Some(slave) => Ok(slave),
+            None => self.master().open_slave(),
+        }
+    }
+
+    /// The function `pty` returns the pair, which is only taken on drop.
+    fn pty(&self) -> &Pty {
+        self.pty.as_ref().expect("the pair is only taken on drop")
+    }
+
+    /// The function `pty_mut` returns the pair mutably, like `pty`.
+    fn pty_mut(&mut self) -> &mut Pty {
+        self.pty.as_mut().expect("the pair is only taken on drop")
+    }
+}
+
+impl<'a> Drop for PooledPty<'a> {
+    /// The destructor function `drop` resets the pair and returns it to
+    /// the pool, a pair which can't be reset or is still the controlling
+    /// terminal of a session is closed instead.
+    fn drop(&mut self) {
+        if let Some(mut pty) = self.pty.take() {
+            if pty.reset().is_ok() {
+                self.pool.ptys.lock().unwrap_or_else(|e| e.into_inner()).push(pty);
+            }
+        }
+    }
+}
+
+impl Pty {
+    /// The constructor function `new` allocates a pair and its settings.
+    fn new() -> io::Result<Self> {
+        let (master, slave) = openpty()?;
+        let termios = master.termios()?;
+        let window_size = master.window_size()?;
+
+        Ok(Pty {
+            master,
+            slave: Some(slave),
+            termios,
+            window_size,
+        })
+    }
+
+    /// The function `reset` reopens the slave if it was taken, restores the
+    /// settings and the window size of the allocation and discards the data
+    /// left by the previous session. It fails with `EBUSY` while a session
+    /// still has the slave as its controlling terminal, which `tcgetsid`
+    /// finds through the master.
+    fn reset(&mut self) -> io::Result<()> {
+        if unsafe { libc::tcgetsid(self.master.as_raw_fd()) } != -1 {
+            return Err(io::Error::from_raw_os_error(libc::EBUSY));
+        }
+        if self.slave.is_none() {
+            self.slave = Some(self.master.open_slave()?);
+        }
+        self.master.apply(&self.termios)?;
+        self.master.set_window_size(self.window_size.0, self.window_size.1)?;
+        self.master.flush_io(FlushQueue::Both)
+    }
+}
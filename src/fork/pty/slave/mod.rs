@@ -4,11 +4,27 @@ use ::descriptor::Descriptor;
 use ::libc;
 
 pub use self::err::{SlaveError, Result};
-use std::os::unix::io::{AsRawFd, RawFd};
+use std::ffi::{CStr, CString, OsStr};
+use std::io;
+use std::mem;
+use std::os::fd::OwnedFd;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::io::{AsRawFd, FromRawFd, RawFd};
+use std::path::PathBuf;
 
+/// The request acquiring the controlling terminal, a `u32` on Darwin
+/// while `ioctl` takes a `c_ulong`.
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+const TIOCSCTTY: libc::c_ulong = libc::TIOCSCTTY as libc::c_ulong;
+#[cfg(not(any(target_os = "macos", target_os = "ios")))]
+use libc::TIOCSCTTY;
+
+/// The struct `Slave` owns the fd of a PTY slave, which is closed on drop.
+/// It's `Send` and `Sync` like `Master`.
 #[derive(Debug)]
 pub struct Slave {
     pty: RawFd,
+    path: Option<CString>,
 }
 
 impl Slave {
@@ -16,7 +32,51 @@ impl Slave {
     pub fn new(path: *const ::libc::c_char) -> Result<Self> {
         match Self::open(path, libc::O_RDWR, None) {
             Err(cause) => Err(SlaveError::BadDescriptor(cause)),
-            Ok(fd) => Ok(Slave { pty: fd }),
+            Ok(fd) => Ok(Slave::with_fd(fd, None)),
+        }
+    }
+
+    /// The constructor function `open_path` opens the Slave interface at
+    /// `path` with `flags`, like `O_NOCTTY` so it doesn't become the
+    /// controlling terminal of a session leader without one. The path is
+    /// kept for `name`, it's allocated by the caller so a child doesn't
+    /// allocate after `fork`.
+    pub(crate) fn open_path(path: CString, flags: libc::c_int) -> Result<Self> {
+        match Self::open(path.as_ptr(), flags, None) {
+            Err(cause) => Err(SlaveError::BadDescriptor(cause)),
+            Ok(fd) => Ok(Slave::with_fd(fd, Some(path))),
+        }
+    }
+
+    /// The constructor function `with_fd` wraps the opened `fd`
+    /// and keeps its `path` for `name`, if known.
+    pub(crate) fn with_fd(fd: RawFd, path: Option<CString>) -> Self {
+        Slave { pty: fd, path }
+    }
+
+    /// Returns the path of the slave device, like `/dev/pts/3`, kept
+    /// since it's opened, or else which `ttyname_r` finds for the fd.
+    pub fn name(&self) -> io::Result<PathBuf> {
+        match self.path {
+            Some(ref path) => Ok(PathBuf::from(OsStr::from_bytes(path.to_bytes()))),
+            None => ttyname(self.as_raw_fd()),
+        }
+    }
+
+    /// Returns a new slave owning a duplicate of the fd with close-on-exec,
+    /// which is closed independently on drop, like to move a borrowed slave
+    /// into `pre_exec`.
+    pub fn try_clone(&self) -> io::Result<Slave> {
+        unsafe {
+            match libc::fcntl(self.as_raw_fd(), libc::F_DUPFD_CLOEXEC, 0) {
+                -1 => Err(io::Error::last_os_error()),
+                fd => {
+                    Ok(Slave {
+                        pty: fd,
+                        path: self.path.clone(),
+                    })
+                }
+            }
         }
     }
 
@@ -28,6 +88,175 @@ impl Slave {
             }
         }
     }
+
+    /// Sets the window size of the terminal, so a program executed
+    /// later sees it from the start.
+    pub fn set_window_size(&self, rows: u16, cols: u16) -> io::Result<()> {
+        let size = libc::winsize {
+            ws_row: rows,
+            ws_col: cols,
+            ws_xpixel: 0,
+            ws_ypixel: 0,
+        };
+
+        unsafe {
+            match libc::ioctl(self.as_raw_fd(), libc::TIOCSWINSZ, &size) {
+                -1 => Err(io::Error::last_os_error()),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    /// Returns the window size of the terminal as `(rows, cols)`.
+    pub fn window_size(&self) -> io::Result<(u16, u16)> {
+        let mut size = libc::winsize {
+            ws_row: 0,
+            ws_col: 0,
+            ws_xpixel: 0,
+            ws_ypixel: 0,
+        };
+
+        unsafe {
+            match libc::ioctl(self.as_raw_fd(), libc::TIOCGWINSZ, &mut size) {
+                -1 => Err(io::Error::last_os_error()),
+                _ => Ok((size.ws_row, size.ws_col)),
+            }
+        }
+    }
+
+    /// Gives up the fd without closing it, like to pass the slave to
+    /// `std::process::Stdio`.
+    pub fn into_owned_fd(self) -> OwnedFd {
+        let fd = self.pty;
+
+        mem::forget(self);
+        unsafe { OwnedFd::from_raw_fd(fd) }
+    }
+
+    /// Pushes `bytes` into the input queue of the terminal, a byte at a
+    /// time with `TIOCSTI`, as if they were typed, so they're read by the
+    /// child and echoed like keystrokes, to replay a recorded session.
+    ///
+    /// It's behind the `tiocsti` feature since `TIOCSTI` lets a process
+    /// inject commands into the shell of another terminal, it's the reason
+    /// the sandboxes forbid it. Without `CAP_SYS_ADMIN` the slave must be
+    /// the controlling terminal of the caller, and Linux 6.2 fails with
+    /// `EIO` when the `dev.tty.legacy_tiocsti` sysctl is off. A partial
+    /// input is left in the queue when a byte fails.
+    #[cfg(feature = "tiocsti")]
+    pub fn push_input(&self, bytes: &[u8]) -> io::Result<()> {
+        for byte in bytes {
+            unsafe {
+                if libc::ioctl(self.as_raw_fd(), libc::TIOCSTI, byte) == -1 {
+                    return Err(io::Error::last_os_error());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Acquires this slave as the controlling terminal of the calling
+    /// process, which must be a session leader without one.
+    pub fn set_controlling_terminal(&self) -> io::Result<()> {
+        unsafe {
+            match libc::ioctl(self.as_raw_fd(), TIOCSCTTY, 0) {
+                -1 => Err(io::Error::last_os_error()),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    /// Makes the calling process the leader of a new session with this
+    /// slave as its controlling terminal, stdin, stdout and stderr.
+    ///
+    /// It's meant to be called in the child by
+    /// `std::os::unix::process::CommandExt::pre_exec`, so the command
+    /// keeps its arguments, environment and working directory handling:
+    ///
+    /// ```rust
+    /// extern crate pty;
+    ///
+    /// use pty::fork::openpty;
+    /// use std::io::Read;
+    /// use std::os::unix::process::CommandExt;
+    /// use std::process::Command;
+    ///
+    /// fn main() {
+    ///   let (mut master, slave) = openpty().unwrap();
+    ///   let mut command = Command::new("tty");
+    ///
+    ///   unsafe { command.pre_exec(move || slave.make_controlling_terminal()) };
+    ///
+    ///   let mut child = command.spawn().unwrap();
+    ///   let mut output = String::new();
+    ///
+    ///   // Drops the parent's slave so the master reads the end of file.
+    ///   drop(command);
+    ///   master.read_to_string(&mut output).unwrap();
+    ///   child.wait().unwrap();
+    ///
+    ///   assert!(output.starts_with("/dev/"));
+    /// }
+    /// ```
+    #[cfg(not(any(target_os = "macos",
+                  target_os = "ios",
+                  target_os = "freebsd",
+                  target_os = "dragonfly",
+                  target_os = "netbsd",
+                  target_os = "openbsd")))]
+    pub fn make_controlling_terminal(&self) -> io::Result<()> {
+        unsafe {
+            if libc::setsid() == -1 {
+                return Err(io::Error::last_os_error());
+            }
+        }
+        self.set_controlling_terminal()?;
+        for &std in &[libc::STDIN_FILENO, libc::STDOUT_FILENO, libc::STDERR_FILENO] {
+            self.dup2(std).map_err(|_| io::Error::last_os_error())?;
+        }
+        Ok(())
+    }
+
+    /// Makes the calling process the leader of a new session with this
+    /// slave as its controlling terminal, stdin, stdout and stderr, with
+    /// `login_tty(3)` of the BSDs which closes a duplicate of the slave.
+    #[cfg(any(target_os = "macos",
+              target_os = "ios",
+              target_os = "freebsd",
+              target_os = "dragonfly",
+              target_os = "netbsd",
+              target_os = "openbsd"))]
+    pub fn make_controlling_terminal(&self) -> io::Result<()> {
+        unsafe {
+            match libc::dup(self.as_raw_fd()) {
+                -1 => Err(io::Error::last_os_error()),
+                fd => {
+                    match libc::login_tty(fd) {
+                        -1 => {
+                            let err = io::Error::last_os_error();
+
+                            libc::close(fd);
+                            Err(err)
+                        }
+                        _ => Ok(()),
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The function `ttyname` returns the path of the terminal `fd`
+/// with the reentrant `ttyname_r`.
+fn ttyname(fd: RawFd) -> io::Result<PathBuf> {
+    let mut buf: [libc::c_char; 128] = [0; 128];
+
+    unsafe {
+        match libc::ttyname_r(fd, buf.as_mut_ptr(), buf.len()) {
+            0 => Ok(PathBuf::from(OsStr::from_bytes(CStr::from_ptr(buf.as_ptr()).to_bytes()))),
+            errno => Err(io::Error::from_raw_os_error(errno)),
+        }
+    }
 }
 
 impl Descriptor for Slave {}
@@ -39,6 +268,14 @@ impl AsRawFd for Slave {
     }
 }
 
+impl FromRawFd for Slave {
+    /// The constructor function `from_raw_fd` wraps a fd already opened
+    /// as a PTY slave elsewhere.
+    unsafe fn from_raw_fd(fd: RawFd) -> Self {
+        Slave { pty: fd, path: None }
+    }
+}
+
 impl Drop for Slave {
     fn drop(&mut self) {
         Descriptor::drop(self);
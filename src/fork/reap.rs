@@ -0,0 +1,52 @@
+use ::libc;
+
+use super::{reap, Fork};
+use std::ops::Deref;
+
+/// The struct `ReapOnDrop` kills and reaps the child of a fork when
+/// dropped, so no child outlives a test which panics.
+#[derive(Debug)]
+pub struct ReapOnDrop {
+    fork: Option<Fork>,
+}
+
+impl ReapOnDrop {
+    /// The constructor function `new` takes ownership of `fork`.
+    pub fn new(fork: Fork) -> Self {
+        ReapOnDrop { fork: Some(fork) }
+    }
+
+    /// The function `into_inner` returns the fork without reaping,
+    /// its child is then left to the caller.
+    pub fn into_inner(mut self) -> Fork {
+        self.fork.take().expect("the fork is only taken once")
+    }
+}
+
+impl Deref for ReapOnDrop {
+    type Target = Fork;
+
+    fn deref(&self) -> &Fork {
+        self.fork.as_ref().expect("the fork is only taken once")
+    }
+}
+
+impl Drop for ReapOnDrop {
+    /// The destructor function `drop` reaps the child,
+    /// after a `SIGKILL` if it's still alive.
+    fn drop(&mut self) {
+        if let Some(ref fork) = self.fork {
+            if let Fork::Parent(pid, _) = *fork {
+                let mut status = 0;
+
+                unsafe {
+                    if libc::waitpid(pid, &mut status, libc::WNOHANG) == 0 {
+                        libc::kill(pid, libc::SIGKILL);
+                        reap(pid);
+                    }
+                }
+                fork.reaped();
+            }
+        }
+    }
+}
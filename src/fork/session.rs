@@ -0,0 +1,173 @@
+use ::libc;
+
+use super::{Fork, ForkBuilder, ForkError, Master, MasterError, Result, WaitStatus};
+use std::io;
+use std::os::unix::io::{AsRawFd, RawFd};
+
+/// The struct `Session` bundles the child of a fork with its master,
+/// the name of its slave and its window size, and kills and reaps the
+/// child when dropped unless it has been waited for.
+///
+/// ```rust
+/// extern crate pty;
+///
+/// use pty::fork::{ForkBuilder, Session, WaitStatus};
+/// use std::io::Write;
+///
+/// fn main() {
+///   let fork = ForkBuilder::new()
+///     .window_size(24, 80)
+///     .spawn("sh", &["-c", "stty size; read x; stty size"])
+///     .unwrap();
+///   let mut session = Session::new(fork).unwrap();
+///
+///   assert!(session.pts_name().starts_with("/dev/"));
+///   assert_eq!(session.master().lines().next().unwrap().unwrap(), "24 80");
+///
+///   session.resize(40, 120).unwrap();
+///   session.write_all(b"\n").unwrap();
+///
+///   // Skips the echo of the input.
+///   assert_eq!(session.master().lines().nth(1).unwrap().unwrap(), "40 120");
+///   assert_eq!(session.wait().unwrap(), WaitStatus::Exited(0));
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Session {
+    fork: Fork,
+    pts_name: String,
+    window_size: (u16, u16),
+    status: Option<WaitStatus>,
+}
+
+impl Session {
+    /// The constructor function `spawn` executes `cmd` with the arguments
+    /// `args` in a new session, like `ForkBuilder::spawn`.
+    pub fn spawn(cmd: &str, args: &[&str]) -> Result<Self> {
+        ForkBuilder::new().spawn(cmd, args).and_then(Session::new)
+    }
+
+    /// The constructor function `new` takes ownership of the parent `fork`,
+    /// it fails with `ForkError::IsChild` in the child, and with
+    /// `ForkError::BadMaster` when the name or the window size of the
+    /// terminal can't be read.
+    pub fn new(fork: Fork) -> Result<Self> {
+        let (pts_name, window_size) = match fork.parent() {
+            None => return Err(ForkError::IsChild),
+            Some((_, master)) => {
+                let pts_name = master.pts_name()
+                    .map_err(|err| ForkError::BadMaster(MasterError::PtsNameFail(err)))?;
+                let window_size = master.window_size()
+                    .map_err(|err| ForkError::BadMaster(MasterError::WindowSizeFail(err)))?;
+
+                (pts_name, window_size)
+            }
+        };
+
+        Ok(Session {
+            fork,
+            pts_name,
+            window_size,
+            status: None,
+        })
+    }
+
+    /// The accessor function `pid` returns the pid of the child.
+    pub fn pid(&self) -> libc::pid_t {
+        self.parent().0
+    }
+
+    /// The accessor function `master` borrows the master.
+    pub fn master(&self) -> &Master {
+        self.parent().1
+    }
+
+    /// The accessor function `pts_name` returns the path of the slave.
+    pub fn pts_name(&self) -> &str {
+        &self.pts_name
+    }
+
+    /// The accessor function `window_size` returns the window size
+    /// as `(rows, cols)`, as last set.
+    pub fn window_size(&self) -> (u16, u16) {
+        self.window_size
+    }
+
+    /// Resizes the terminal to `rows` and `cols`, the child receives `SIGWINCH`.
+    pub fn resize(&mut self, rows: u16, cols: u16) -> io::Result<()> {
+        self.master().set_window_size(rows, cols)?;
+        self.window_size = (rows, cols);
+        Ok(())
+    }
+
+    /// Sends the signal `signal` to the child.
+    pub fn kill(&self, signal: libc::c_int) -> Result<()> {
+        self.fork.kill(signal)
+    }
+
+    /// Waits until the child is terminated and returns how,
+    /// again on the later calls.
+    pub fn wait(&mut self) -> Result<WaitStatus> {
+        match self.status {
+            Some(status) => Ok(status),
+            None => {
+                let status = self.fork.wait_status()?;
+
+                self.status = Some(status);
+                Ok(status)
+            }
+        }
+    }
+
+    /// Checks without blocking whether the child is terminated,
+    /// returns how or none if it's still alive.
+    pub fn try_wait(&mut self) -> Result<Option<WaitStatus>> {
+        if self.status.is_none() {
+            self.status = self.fork.try_wait_status()?;
+        }
+        Ok(self.status)
+    }
+
+    /// The function `parent` returns the pid and the master of the fork,
+    /// which `new` checked is the parent.
+    fn parent(&self) -> (libc::pid_t, &Master) {
+        self.fork.parent().expect("the session is the parent")
+    }
+}
+
+impl AsRawFd for Session {
+    /// The accessor function `as_raw_fd` returns the fd of the master.
+    fn as_raw_fd(&self) -> RawFd {
+        self.master().as_raw_fd()
+    }
+}
+
+impl io::Read for Session {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        io::Read::read(&mut self.master(), buf)
+    }
+}
+
+impl io::Write for Session {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self.fork {
+            Fork::Parent(_, ref mut master) => master.write(buf),
+            Fork::Child(_) => Err(io::Error::other("the session is not the parent")),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Drop for Session {
+    /// The destructor function `drop` reaps the child unless it has been
+    /// waited for, after a `SIGKILL` if it's still alive.
+    fn drop(&mut self) {
+        if let Ok(None) = self.try_wait() {
+            let _ = self.kill(libc::SIGKILL);
+            let _ = self.wait();
+        }
+    }
+}
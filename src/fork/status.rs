@@ -0,0 +1,41 @@
+use ::libc;
+
+/// The enum `WaitStatus` describes how the child has terminated,
+/// or has been stopped or continued by job control.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WaitStatus {
+    /// The child exited normally with the exit status.
+    Exited(libc::c_int),
+    /// The child was terminated by the signal.
+    Signaled(libc::c_int),
+    /// The child was stopped by the signal.
+    Stopped(libc::c_int),
+    /// The stopped child was continued by `SIGCONT`.
+    Continued,
+}
+
+impl WaitStatus {
+    /// The constructor function `from_raw` decodes the status word
+    /// filled by `waitpid`, or returns none for another state.
+    pub fn from_raw(status: libc::c_int) -> Option<Self> {
+        if libc::WIFEXITED(status) {
+            Some(WaitStatus::Exited(libc::WEXITSTATUS(status)))
+        } else if libc::WIFSIGNALED(status) {
+            Some(WaitStatus::Signaled(libc::WTERMSIG(status)))
+        } else if libc::WIFSTOPPED(status) {
+            Some(WaitStatus::Stopped(libc::WSTOPSIG(status)))
+        } else if libc::WIFCONTINUED(status) {
+            Some(WaitStatus::Continued)
+        } else {
+            None
+        }
+    }
+
+    /// Returns whether the child has terminated, rather than stopped or continued.
+    pub fn is_terminated(&self) -> bool {
+        match *self {
+            WaitStatus::Exited(_) | WaitStatus::Signaled(_) => true,
+            WaitStatus::Stopped(_) | WaitStatus::Continued => false,
+        }
+    }
+}
@@ -0,0 +1,22 @@
+/// The struct `StdStreams` selects which of the standard streams of
+/// the child are the slave, the others are inherited from the parent.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StdStreams {
+    /// The standard input.
+    pub stdin: bool,
+    /// The standard output.
+    pub stdout: bool,
+    /// The standard error, which can be left out to capture it apart.
+    pub stderr: bool,
+}
+
+impl Default for StdStreams {
+    /// The constructor function `default` selects the three streams.
+    fn default() -> Self {
+        StdStreams {
+            stdin: true,
+            stdout: true,
+            stderr: true,
+        }
+    }
+}
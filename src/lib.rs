@@ -19,7 +19,7 @@
 //! This crate depends on followings:
 //!
 //! * `libc` library
-//! * POSIX environment
+//! * POSIX environment (Linux, macOS or a BSD)
 //!
 //! ## Usage
 //!
@@ -44,11 +44,8 @@
 //!
 //! ```rust
 //! extern crate pty;
-//! extern crate libc;
 //!
-//! use std::ffi::CString;
 //! use std::io::Read;
-//! use std::ptr;
 //!
 //! use pty::fork::*;
 //!
@@ -66,10 +63,7 @@
 //!   }
 //!   else {
 //!     // Child process just exec `tty`
-//!     let cmd  = CString::new("tty").unwrap().as_ptr();
-//!     let args = [cmd, ptr::null()].as_mut_ptr();
-//!
-//!     unsafe { libc::execvp(cmd, args) };
+//!     exec("tty", &[]);
 //!   }
 //! }
 //! ```
@@ -92,10 +86,33 @@
 
 extern crate libc;
 extern crate errno;
+#[cfg(feature = "mio")]
+extern crate mio;
+#[cfg(feature = "tokio")]
+extern crate tokio;
 
 mod ffi;
 mod descriptor;
+mod pump;
 pub mod fork;
 pub mod prelude;
 
-const DEFAULT_PTMX: &'static str = "/dev/ptmx";
+pub use pump::pump;
+use std::env;
+use std::sync::OnceLock;
+
+/// The path of the PTMX, left to `PTY_PTMX_PATH` for the systems
+/// which have another one.
+const DEFAULT_PTMX: &str = "/dev/ptmx";
+
+/// The environment variable overriding the path of the PTMX.
+const PTMX_PATH_VAR: &str = "PTY_PTMX_PATH";
+
+/// The function `default_ptmx` returns the path of the PTMX for the
+/// platform, or the one set by `PTY_PTMX_PATH` for the containers where
+/// it lives elsewhere. The variable is read once, on the first call.
+pub fn default_ptmx() -> &'static str {
+    static PATH: OnceLock<String> = OnceLock::new();
+
+    PATH.get_or_init(|| env::var(PTMX_PATH_VAR).unwrap_or_else(|_| DEFAULT_PTMX.to_string()))
+}
@@ -1,3 +1,6 @@
-pub use super::fork::{Fork, ForkError};
-pub use super::fork::{Master, MasterError};
+pub use super::fork::{Detached, Fork, ForkBuilder, ForkError, ReapOnDrop, Session, StdStreams,
+                      WaitStatus};
+pub use super::fork::{Master, MasterError, MasterReader, MasterWriter};
 pub use super::fork::{Slave, SlaveError};
+pub use super::fork::{exec, openpty, release_controlling_terminal};
+pub use super::{default_ptmx, pump};
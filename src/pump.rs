@@ -0,0 +1,140 @@
+use ::libc;
+
+use fork::Master;
+use std::io::{self, Read};
+use std::mem;
+use std::os::unix::io::{AsRawFd, RawFd};
+
+/// The function `pump` relays `input` to the master and the master to
+/// `output`, like a terminal emulator, until the master reaches the end
+/// of file. The input is put into raw mode if it's a terminal, so the
+/// keys reach the child unprocessed, and restored on return. Once the
+/// input ends, the end of file is sent to the child with `send_eof`,
+/// twice after a partial line since the first one only flushes it.
+pub fn pump(master: &Master, input: RawFd, output: RawFd) -> io::Result<()> {
+    let _raw = RawMode::new(input)?;
+    let mut fds = [libc::pollfd {
+                       fd: master.as_raw_fd(),
+                       events: libc::POLLIN,
+                       revents: 0,
+                   },
+                   libc::pollfd {
+                       fd: input,
+                       events: libc::POLLIN,
+                       revents: 0,
+                   }];
+    let mut buf = [0; 4096];
+    let mut reader = master;
+    let mut line_start = true;
+
+    loop {
+        if unsafe { libc::poll(fds.as_mut_ptr(), fds.len() as libc::nfds_t, -1) } == -1 {
+            let err = io::Error::last_os_error();
+
+            if err.kind() == io::ErrorKind::Interrupted {
+                continue;
+            }
+            return Err(err);
+        }
+        if fds[0].revents != 0 {
+            match reader.read(&mut buf)? {
+                0 => return Ok(()),
+                len => write_all(output, &buf[..len])?,
+            }
+        }
+        if fds[1].revents != 0 {
+            match read(input, &mut buf)? {
+                // A negative fd is ignored by `poll`, once the input is over.
+                0 => {
+                    fds[1].fd = -1;
+                    if !line_start {
+                        master.send_eof()?;
+                    }
+                    master.send_eof()?;
+                }
+                len => {
+                    master.write_blocking_all(&buf[..len])?;
+                    line_start = buf[len - 1] == b'\n';
+                }
+            }
+        }
+    }
+}
+
+/// The struct `RawMode` restores the terminal settings of a fd on drop.
+struct RawMode {
+    fd: RawFd,
+    previous: Option<libc::termios>,
+}
+
+impl RawMode {
+    /// The constructor function `new` puts `fd` into raw mode if it's a terminal.
+    fn new(fd: RawFd) -> io::Result<Self> {
+        unsafe {
+            if libc::isatty(fd) == 0 {
+                return Ok(RawMode {
+                    fd,
+                    previous: None,
+                });
+            }
+
+            let mut previous: libc::termios = mem::zeroed();
+
+            if libc::tcgetattr(fd, &mut previous) == -1 {
+                return Err(io::Error::last_os_error());
+            }
+
+            let mut raw = previous;
+
+            libc::cfmakeraw(&mut raw);
+            if libc::tcsetattr(fd, libc::TCSANOW, &raw) == -1 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(RawMode {
+                fd,
+                previous: Some(previous),
+            })
+        }
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        if let Some(ref previous) = self.previous {
+            unsafe { libc::tcsetattr(self.fd, libc::TCSANOW, previous) };
+        }
+    }
+}
+
+/// The function `read` reads from `fd`, it retries on `EINTR`.
+fn read(fd: RawFd, buf: &mut [u8]) -> io::Result<usize> {
+    loop {
+        match unsafe { libc::read(fd, buf.as_mut_ptr() as *mut libc::c_void, buf.len()) } {
+            -1 => {
+                let err = io::Error::last_os_error();
+
+                if err.kind() != io::ErrorKind::Interrupted {
+                    return Err(err);
+                }
+            }
+            len => return Ok(len as usize),
+        }
+    }
+}
+
+/// The function `write_all` writes the whole `buf` to `fd`, it retries on `EINTR`.
+fn write_all(fd: RawFd, mut buf: &[u8]) -> io::Result<()> {
+    while !buf.is_empty() {
+        match unsafe { libc::write(fd, buf.as_ptr() as *const libc::c_void, buf.len()) } {
+            -1 => {
+                let err = io::Error::last_os_error();
+
+                if err.kind() != io::ErrorKind::Interrupted {
+                    return Err(err);
+                }
+            }
+            len => buf = &buf[len as usize..],
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,31 @@
+#![cfg(feature = "tokio")]
+
+extern crate pty;
+extern crate tokio;
+
+use self::pty::fork::AsyncMaster;
+use self::pty::prelude::*;
+use self::tokio::io::{AsyncReadExt, AsyncWriteExt};
+use self::tokio::runtime::Builder;
+
+#[test]
+fn it_async_master() {
+    let fork = Fork::spawn("sh", &["-c", "read x; echo got $x; read y"], &[]).unwrap();
+    let runtime = Builder::new_current_thread().enable_io().build().unwrap();
+    let _guard = runtime.enter();
+    let mut master = AsyncMaster::new(fork.is_parent().unwrap()).unwrap();
+    let mut output = String::new();
+    let mut buf = [0; 64];
+
+    runtime.block_on(master.write_all(b"async\n")).unwrap();
+
+    while !output.contains("got async") {
+        let len = runtime.block_on(master.read(&mut buf)).unwrap();
+
+        assert!(len > 0);
+        output.push_str(&String::from_utf8_lossy(&buf[..len]));
+    }
+
+    runtime.block_on(master.write_all(b"\n")).unwrap();
+    fork.wait().unwrap();
+}
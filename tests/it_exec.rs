@@ -0,0 +1,11 @@
+extern crate pty;
+
+use self::pty::prelude::*;
+
+use std::io::ErrorKind;
+
+#[test]
+fn it_exec_fail() {
+    assert_eq!(exec("/nonexistent/command", &["arg"]).kind(), ErrorKind::NotFound);
+    assert_eq!(exec("nul\0byte", &[]).kind(), ErrorKind::InvalidInput);
+}
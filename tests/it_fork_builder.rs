@@ -0,0 +1,285 @@
+extern crate pty;
+extern crate libc;
+
+use self::pty::prelude::*;
+
+use std::io::prelude::*;
+use std::string::String;
+
+#[test]
+fn it_fork_builder() {
+    let fork = ForkBuilder::new()
+        .term("xterm-256color")
+        .window_size(40, 120)
+        .spawn("sh", &["-c", "echo $TERM; stty size"])
+        .unwrap();
+    let mut master = fork.is_parent().unwrap();
+    let mut string = String::new();
+
+    master.read_to_string(&mut string).unwrap_or_else(|e| panic!("{}", e));
+
+    assert!(string.contains("xterm-256color"));
+    assert!(string.contains("40 120"));
+    assert_eq!(fork.wait_status().unwrap(), WaitStatus::Exited(0));
+}
+
+#[test]
+fn it_fork_builder_cwd() {
+    let fork = ForkBuilder::new().cwd("/").spawn("sh", &["-c", "pwd; read x"]).unwrap();
+    let mut master = fork.is_parent().unwrap();
+
+    assert_eq!(master.lines().next().unwrap().unwrap(), "/");
+    master.write_all(b"\n").unwrap();
+    fork.wait().unwrap();
+
+    match ForkBuilder::new().cwd("/nonexistent").spawn("pwd", &[]) {
+        Err(ForkError::ChdirFail(err)) => assert_eq!(err.raw_os_error(), Some(libc::ENOENT)),
+        other => panic!("{:?}", other),
+    }
+
+    // The directory is changed with the user of the child, after `setuid`.
+    if unsafe { libc::getuid() } == 0 {
+        let dir = std::env::temp_dir().join(format!("pty-cwd-{}", std::process::id()));
+
+        std::fs::create_dir(&dir).unwrap();
+        std::fs::set_permissions(&dir, std::os::unix::fs::PermissionsExt::from_mode(0o700))
+            .unwrap();
+
+        match ForkBuilder::new().user(65534).cwd(&dir).spawn("pwd", &[]) {
+            Err(ForkError::ChdirFail(err)) => assert_eq!(err.raw_os_error(), Some(libc::EACCES)),
+            other => panic!("{:?}", other),
+        }
+        std::fs::remove_dir(&dir).unwrap();
+    }
+}
+
+#[test]
+fn it_fork_builder_hangup_on_drop() {
+    let fork = ForkBuilder::new()
+        .hangup_on_drop(true)
+        .spawn("sh", &["-c", "echo ready; exec sleep 10"])
+        .unwrap();
+    // The duplicate keeps the terminal open, so only the fork hangs up the child.
+    let master = fork.is_parent().unwrap();
+    let pid = fork.pgid().unwrap();
+    let mut status = 0;
+
+    master.lines().next().unwrap().unwrap();
+    drop(fork);
+
+    assert_eq!(unsafe { libc::waitpid(pid, &mut status, 0) }, pid);
+    assert_eq!(WaitStatus::from_raw(status), Some(WaitStatus::Signaled(libc::SIGHUP)));
+}
+
+#[test]
+fn it_fork_builder_hangup_on_close() {
+    let fork = ForkBuilder::new()
+        .hangup_on_drop(true)
+        .spawn("sh", &["-c", "echo ready; exec sleep 10"])
+        .unwrap();
+    // The duplicate keeps the terminal open, so only `close` hangs up the child.
+    let duplicate = fork.is_parent().unwrap();
+    let mut status = 0;
+
+    duplicate.lines().next().unwrap().unwrap();
+    match fork {
+        Fork::Parent(pid, master) => {
+            master.close().unwrap();
+
+            assert_eq!(unsafe { libc::waitpid(pid, &mut status, 0) }, pid);
+            assert_eq!(WaitStatus::from_raw(status), Some(WaitStatus::Signaled(libc::SIGHUP)));
+        }
+        Fork::Child(_) => unreachable!(),
+    }
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn it_fork_builder_reset_signals() {
+    // Rust ignores `SIGPIPE`, which the child inherits unless it's reset.
+    let sigpipe = 1 << (libc::SIGPIPE - 1);
+
+    assert!(ignored_signals(ForkBuilder::new().reset_signals(false)) & sigpipe != 0);
+    assert_eq!(ignored_signals(ForkBuilder::new().reset_signals(true)) & sigpipe, 0);
+}
+
+#[cfg(target_os = "linux")]
+fn ignored_signals(builder: &ForkBuilder) -> u64 {
+    let fork = builder.spawn("grep", &["SigIgn", "/proc/self/status"]).unwrap();
+    let master = fork.is_parent().unwrap();
+    let line = master.lines().next().unwrap().unwrap();
+
+    fork.wait().unwrap();
+    u64::from_str_radix(line.trim_start_matches("SigIgn:").trim(), 16).unwrap()
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn it_fork_builder_std_streams() {
+    let streams = StdStreams { stderr: false, ..StdStreams::default() };
+    let fork = ForkBuilder::new()
+        .std_streams(streams)
+        .spawn("sh", &["-c", "[ /proc/$$/fd/1 -ef /proc/$$/fd/2 ] || echo apart; read x"])
+        .unwrap();
+    let mut master = fork.is_parent().unwrap();
+
+    assert_eq!(master.lines().next().unwrap().unwrap(), "apart");
+    master.write_all(b"\n").unwrap();
+    fork.wait().unwrap();
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn it_fork_builder_inherit_fd() {
+    use std::fs::File;
+    use std::os::unix::io::{AsRawFd, FromRawFd};
+
+    let mut fds = [0; 2];
+
+    assert_eq!(unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC) }, 0);
+
+    let (mut reader, writer) = unsafe { (File::from_raw_fd(fds[0]), File::from_raw_fd(fds[1])) };
+    let fork = ForkBuilder::new()
+        .inherit_fd(3, writer.as_raw_fd())
+        .spawn("sh", &["-c", "echo side >&3"])
+        .unwrap();
+    let mut string = String::new();
+
+    // The child holds the only other write end once the parent's is closed.
+    drop(writer);
+    reader.read_to_string(&mut string).unwrap();
+    fork.wait().unwrap();
+
+    assert_eq!(string, "side\n");
+}
+
+#[test]
+fn it_fork_builder_inherit_fd_fail() {
+    match ForkBuilder::new().inherit_fd(10, 99999).spawn("true", &[]) {
+        Err(ForkError::InheritFdFail(err)) => assert_eq!(err.raw_os_error(), Some(libc::EBADF)),
+        other => panic!("{:?}", other),
+    }
+
+    // The inherited fds cover the report pipe, whatever its fd.
+    let null = std::fs::File::open("/dev/null").unwrap();
+    let mut builder = ForkBuilder::new();
+
+    for child_fd in 3..64 {
+        builder.inherit_fd(child_fd, std::os::unix::io::AsRawFd::as_raw_fd(&null));
+    }
+    match builder.spawn("/nonexistent", &[]) {
+        Err(ForkError::ExecFail(err)) => assert_eq!(err.raw_os_error(), Some(libc::ENOENT)),
+        other => panic!("{:?}", other),
+    }
+}
+
+#[test]
+fn it_fork_builder_env_clear() {
+    assert!(std::env::var_os("HOME").is_some());
+
+    let fork = ForkBuilder::new()
+        .env_clear()
+        .env("ONLY", "set")
+        .spawn("sh", &["-c", "echo \"$HOME:$ONLY\"; read x"])
+        .unwrap();
+    let mut master = fork.is_parent().unwrap();
+
+    assert_eq!(master.lines().next().unwrap().unwrap(), ":set");
+    master.write_all(b"\n").unwrap();
+    fork.wait().unwrap();
+    assert!(std::env::var_os("HOME").is_some());
+}
+
+#[test]
+fn it_fork_builder_env_path() {
+    // The command is looked up with the `PATH` of the child.
+    match ForkBuilder::new().env("PATH", "/nonexistent").spawn("sh", &[]) {
+        Err(ForkError::ExecFail(err)) => assert_eq!(err.raw_os_error(), Some(libc::ENOENT)),
+        other => panic!("{:?}", other),
+    }
+
+    let fork = ForkBuilder::new()
+        .env("PATH", "/nonexistent::/bin:/usr/bin")
+        .env("PTY_OVERRIDE", "first")
+        .env("PTY_OVERRIDE", "second")
+        .spawn("sh", &["-c", "echo \"$PTY_OVERRIDE\"; read x"])
+        .unwrap();
+    let mut master = fork.is_parent().unwrap();
+
+    assert_eq!(master.lines().next().unwrap().unwrap(), "second");
+    master.write_all(b"\n").unwrap();
+    fork.wait().unwrap();
+}
+
+#[test]
+fn it_fork_builder_credentials() {
+    // More groups than `NGROUPS_MAX`, so it fails even for root.
+    match ForkBuilder::new().groups(&vec![0; 1 << 17]).spawn("true", &[]) {
+        Err(ForkError::SetgroupsFail(_)) => {}
+        other => panic!("{:?}", other),
+    }
+
+    if unsafe { libc::getuid() } != 0 {
+        return;
+    }
+
+    let fork = ForkBuilder::new()
+        .groups(&[65533])
+        .group(65534)
+        .user(65534)
+        .spawn("sh", &["-c", "id -u; id -g; id -G; read x"])
+        .unwrap();
+    let mut master = fork.is_parent().unwrap();
+    let lines: Vec<String> = master.lines().take(3).map(|line| line.unwrap()).collect();
+
+    assert_eq!(lines, ["65534", "65534", "65534 65533"]);
+    master.write_all(b"\n").unwrap();
+    fork.wait().unwrap();
+}
+
+#[test]
+fn it_fork_builder_arg0() {
+    let fork = ForkBuilder::new().arg0("pty-shell").spawn("sh", &["-c", "echo $0; read x"]).unwrap();
+    let mut master = fork.is_parent().unwrap();
+
+    assert_eq!(master.lines().next().unwrap().unwrap(), "pty-shell");
+    master.write_all(b"\n").unwrap();
+    fork.wait().unwrap();
+
+    match ForkBuilder::new().arg0("s\0h").spawn("sh", &[]) {
+        Err(ForkError::InvalidString) => {}
+        other => panic!("{:?}", other),
+    }
+}
+
+#[test]
+fn it_fork_builder_login_shell() {
+    // The profile of the login shell isn't looked up in a missing home.
+    let fork = ForkBuilder::new().env("HOME", "/nonexistent").login_shell("/bin/sh").unwrap();
+    let mut master = fork.is_parent().unwrap();
+    let mut string = String::new();
+
+    master.write_all(b"echo name=$0\nexit\n").unwrap();
+    master.read_to_string(&mut string).unwrap_or_else(|e| panic!("{}", e));
+
+    assert!(string.contains("name=-sh"));
+    assert_eq!(fork.wait_status().unwrap(), WaitStatus::Exited(0));
+}
+
+#[test]
+fn it_fork_builder_no_controlling_terminal() {
+    let script = "(: < /dev/tty) 2> /dev/null && echo has || echo none; read x";
+    let fork = ForkBuilder::new().no_controlling_terminal().spawn("sh", &["-c", script]).unwrap();
+    let mut master = fork.is_parent().unwrap();
+
+    assert_eq!(master.lines().next().unwrap().unwrap(), "none");
+    master.write_all(b"\n").unwrap();
+    assert_eq!(fork.wait_status().unwrap(), WaitStatus::Exited(0));
+
+    let fork = ForkBuilder::new().spawn("sh", &["-c", script]).unwrap();
+    let mut master = fork.is_parent().unwrap();
+
+    assert_eq!(master.lines().next().unwrap().unwrap(), "has");
+    master.write_all(b"\n").unwrap();
+    assert_eq!(fork.wait_status().unwrap(), WaitStatus::Exited(0));
+}
@@ -0,0 +1,37 @@
+#![cfg(feature = "mio")]
+
+extern crate pty;
+extern crate mio;
+
+use self::pty::prelude::*;
+use self::mio::{Events, Interest, Poll, Token};
+
+use std::io::prelude::*;
+use std::time::Duration;
+
+#[test]
+fn it_mio_source() {
+    let fork = Fork::spawn("sh", &["-c", "read x; echo got $x; read y"], &[]).unwrap();
+    let mut master = fork.is_parent().unwrap();
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(4);
+    let mut output = String::new();
+    let mut buf = [0; 64];
+
+    master.set_nonblocking(true).unwrap();
+    poll.registry().register(&mut master, Token(0), Interest::READABLE).unwrap();
+    master.write_all(b"mio\n").unwrap();
+
+    while !output.contains("got mio") {
+        poll.poll(&mut events, Some(Duration::from_secs(5))).unwrap();
+        assert!(!events.is_empty());
+
+        while let Ok(len) = master.read(&mut buf) {
+            output.push_str(&String::from_utf8_lossy(&buf[..len]));
+        }
+    }
+
+    poll.registry().deregister(&mut master).unwrap();
+    master.write_all(b"\n").unwrap();
+    fork.wait().unwrap();
+}
@@ -0,0 +1,136 @@
+extern crate pty;
+extern crate libc;
+
+use self::pty::prelude::*;
+use self::pty::fork::ReadOutcome;
+
+use std::io::prelude::*;
+use std::io::ErrorKind;
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[test]
+fn it_nonblocking() {
+    let fork = Fork::spawn("sleep", &["10"], &[]).unwrap();
+    let mut master = fork.is_parent().unwrap();
+    let mut buf = [0; 64];
+
+    master.set_nonblocking(true).unwrap();
+
+    assert_eq!(master.read(&mut buf).unwrap_err().kind(), ErrorKind::WouldBlock);
+
+    fork.kill(libc::SIGKILL).unwrap();
+    fork.wait().unwrap();
+}
+
+#[test]
+fn it_read_timeout() {
+    let fork = Fork::spawn("sh", &["-c", "sleep 1; echo late!; sleep 10"], &[]).unwrap();
+    let master = fork.is_parent().unwrap();
+    let mut buf = [0; 64];
+
+    assert_eq!(master.read_timeout(&mut buf, Duration::from_millis(10)).unwrap(), None);
+
+    let len = master.read_timeout(&mut buf, Duration::from_secs(5)).unwrap().unwrap();
+
+    assert!(String::from_utf8_lossy(&buf[..len]).contains("late!"));
+
+    fork.kill(libc::SIGKILL).unwrap();
+    fork.wait().unwrap();
+}
+
+#[test]
+fn it_read_exact_timeout() {
+    let fork = Fork::spawn("sh", &["-c", "printf abc; sleep 1; printf def; sleep 10"], &[]).unwrap();
+    let master = fork.is_parent().unwrap();
+    let mut buf = [0; 6];
+
+    master.read_exact_timeout(&mut buf, Duration::from_secs(5)).unwrap();
+
+    assert_eq!(&buf, b"abcdef");
+    assert_eq!(master.read_exact_timeout(&mut buf, Duration::from_millis(10)).unwrap_err().kind(),
+               ErrorKind::TimedOut);
+
+    fork.kill(libc::SIGKILL).unwrap();
+    fork.wait().unwrap();
+}
+
+#[test]
+fn it_read_available() {
+    let fork = Fork::spawn("sh", &["-c", "read x"], &[]).unwrap();
+    let mut master = fork.is_parent().unwrap();
+    let mut buf = [0; 64];
+
+    master.set_nonblocking(true).unwrap();
+
+    assert_eq!(master.read_available(&mut buf).unwrap(), ReadOutcome::WouldBlock);
+
+    master.write_all(b"\n").unwrap();
+    fork.wait().unwrap();
+
+    loop {
+        match master.read_available(&mut buf).unwrap() {
+            ReadOutcome::Data(_) => continue,
+            ReadOutcome::Eof => break,
+            ReadOutcome::WouldBlock => thread::sleep(Duration::from_millis(10)),
+        }
+    }
+}
+
+#[test]
+fn it_read_until() {
+    let fork = Fork::spawn("sh", &["-c", "printf 'log'; sleep 1; printf 'in: '; sleep 10"], &[]).unwrap();
+    let master = fork.is_parent().unwrap();
+
+    assert_eq!(master.read_until(b"n: ", Duration::from_secs(5)).unwrap(), b"login: ");
+    assert_eq!(master.read_until(b"$ ", Duration::from_millis(10)).unwrap_err().kind(),
+               ErrorKind::TimedOut);
+
+    fork.kill(libc::SIGKILL).unwrap();
+    fork.wait().unwrap();
+}
+
+#[test]
+fn it_write_all_timeout() {
+    let fork = Fork::spawn("sleep", &["10"], &[]).unwrap();
+    let master = fork.is_parent().unwrap();
+
+    master.write_all_timeout(b"fits\n", Duration::from_secs(1)).unwrap();
+    assert_eq!(master.write_all_timeout(&[b'a'; 1 << 20], Duration::from_millis(200))
+                     .unwrap_err()
+                     .kind(),
+               ErrorKind::TimedOut);
+
+    fork.kill(libc::SIGKILL).unwrap();
+    fork.wait().unwrap();
+}
+
+#[test]
+fn it_write_all_timeout_closed() {
+    let (master, slave) = openpty().unwrap();
+    let start = Instant::now();
+
+    drop(slave);
+    assert!(master.write_all_timeout(&[b'a'; 1 << 20], Duration::from_secs(5)).is_err());
+    assert!(start.elapsed() < Duration::from_secs(5));
+}
+
+#[test]
+fn it_write_blocking_all() {
+    let fork = Fork::spawn("sh", &["-c", "stty -echo; echo ready; wc -c"], &[]).unwrap();
+    let master = fork.is_parent().unwrap();
+    let line = [b'a'; 63].iter().chain(b"\n").cloned().collect::<Vec<u8>>();
+    let input = line.repeat(2000);
+
+    master.read_until(b"ready\r\n", Duration::from_secs(5)).unwrap();
+    master.set_nonblocking(true).unwrap();
+    master.write_blocking_all(&input).unwrap();
+    // The terminal buffer can still be full for the end of file.
+    master.set_nonblocking(false).unwrap();
+    master.send_eof().unwrap();
+
+    let count = master.read_until(b"\r\n", Duration::from_secs(5)).unwrap();
+
+    assert_eq!(String::from_utf8_lossy(&count).trim(), input.len().to_string());
+    fork.wait().unwrap();
+}
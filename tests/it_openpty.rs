@@ -0,0 +1,346 @@
+extern crate pty;
+extern crate libc;
+
+use self::pty::prelude::*;
+use self::pty::fork::FlushQueue;
+
+use std::env;
+use std::ffi::CString;
+use std::fs;
+use std::io::prelude::*;
+use std::io::{ErrorKind, IoSlice, IoSliceMut};
+use std::os::unix::fs::MetadataExt;
+use std::os::unix::io::{AsRawFd, FromRawFd};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[test]
+fn it_openpty() {
+    let (mut master, slave) = openpty().unwrap();
+    let mut buf = [0u8; 64];
+
+    master.write_all(b"openpty!\n").unwrap();
+
+    let len = unsafe { libc::read(slave.as_raw_fd(), buf.as_mut_ptr() as *mut _, buf.len()) };
+
+    assert_eq!(&buf[..len as usize], b"openpty!\n");
+
+}
+
+#[test]
+fn it_master_is_cloexec() {
+    let (master, _slave) = openpty().unwrap();
+    let flags = unsafe { libc::fcntl(master.as_raw_fd(), libc::F_GETFD) };
+
+    assert!(flags & libc::FD_CLOEXEC != 0);
+
+    master.set_cloexec(false).unwrap();
+
+    let flags = unsafe { libc::fcntl(master.as_raw_fd(), libc::F_GETFD) };
+
+    assert!(flags & libc::FD_CLOEXEC == 0);
+
+}
+
+#[test]
+fn it_pts_name() {
+    let (master, _slave) = openpty().unwrap();
+
+    assert!(master.pts_name().unwrap().starts_with("/dev/"));
+}
+
+#[test]
+fn it_close() {
+    let (master, _slave) = openpty().unwrap();
+
+    master.close().unwrap();
+}
+
+#[test]
+fn it_flush_io() {
+    let (master, slave) = openpty().unwrap();
+    let mut buf = [0u8; 64];
+
+    unsafe { libc::write(slave.as_raw_fd(), b"discarded\n".as_ptr() as *const _, 10) };
+    master.read_timeout(&mut buf[..1], Duration::from_secs(1)).unwrap();
+    master.flush_io(FlushQueue::Input).unwrap();
+
+    assert_eq!(master.read_timeout(&mut buf, Duration::from_millis(10)).unwrap(), None);
+}
+
+#[test]
+fn it_open_with() {
+    let master = Master::open_with(libc::O_NONBLOCK).unwrap();
+    let flags = unsafe { libc::fcntl(master.as_raw_fd(), libc::F_GETFL) };
+
+    assert!(flags & libc::O_NONBLOCK != 0);
+    assert_eq!(flags & libc::O_ACCMODE, libc::O_RDWR);
+}
+
+#[test]
+fn it_vectored() {
+    let (mut master, slave) = openpty().unwrap();
+    let mut buf = [0u8; 64];
+
+    let bufs = [IoSlice::new(b"vec"), IoSlice::new(b""), IoSlice::new(b"tored\n")];
+
+    assert_eq!(master.write_vectored(&bufs).unwrap(), 9);
+
+    let len = unsafe { libc::read(slave.as_raw_fd(), buf.as_mut_ptr() as *mut _, buf.len()) };
+
+    assert_eq!(&buf[..len as usize], b"vectored\n");
+
+    unsafe { libc::write(slave.as_raw_fd(), b"vectored".as_ptr() as *const _, 8) };
+
+    let (mut head, mut tail) = ([0u8; 3], [0u8; 5]);
+
+    {
+        let mut bufs = [IoSliceMut::new(&mut head), IoSliceMut::new(&mut tail)];
+
+        assert_eq!(master.read_vectored(&mut bufs).unwrap(), 8);
+    }
+    assert_eq!((&head, &tail), (b"vec", b"tored"));
+}
+
+#[test]
+fn it_slave_into_owned_fd() {
+    let (master, slave) = openpty().unwrap();
+    let output = Command::new("tty").stdin(Stdio::from(slave.into_owned_fd())).output().unwrap();
+
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), master.pts_name().unwrap());
+}
+
+#[test]
+fn it_bytes_available() {
+    let (master, slave) = openpty().unwrap();
+
+    assert_eq!(master.bytes_available().unwrap(), 0);
+
+    unsafe { libc::write(slave.as_raw_fd(), b"pending".as_ptr() as *const _, 7) };
+
+    // The data reaches the master asynchronously.
+    let deadline = Instant::now() + Duration::from_secs(5);
+
+    while master.bytes_available().unwrap() == 0 && Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(10));
+    }
+    assert_eq!(master.bytes_available().unwrap(), 7);
+}
+
+#[test]
+fn it_output_queue_len() {
+    let (mut master, slave) = openpty().unwrap();
+    let mut pending: libc::c_int = 0;
+
+    assert_eq!(master.output_queue_len().unwrap(), 0);
+
+    master.write_all(b"queue\n").unwrap();
+
+    // The input the child hasn't read is counted on the slave.
+    let deadline = Instant::now() + Duration::from_secs(5);
+
+    while pending == 0 && Instant::now() < deadline {
+        assert_ne!(unsafe { libc::ioctl(slave.as_raw_fd(), libc::FIONREAD, &mut pending) }, -1);
+        thread::sleep(Duration::from_millis(10));
+    }
+    assert_eq!(pending, 6);
+    assert!(master.output_queue_len().unwrap() <= 6);
+}
+
+#[test]
+fn it_read_text() {
+    let (master, slave) = openpty().unwrap();
+
+    // The "é" is split across the writes and followed by an invalid byte.
+    unsafe { libc::write(slave.as_raw_fd(), b"a\xc3".as_ptr() as *const _, 2) };
+    assert_eq!(master.read_text(Duration::from_secs(5)).unwrap(), "a");
+
+    unsafe { libc::write(slave.as_raw_fd(), b"\xa9\xffb".as_ptr() as *const _, 3) };
+    assert_eq!(master.read_text(Duration::from_secs(5)).unwrap(), "\u{e9}\u{fffd}b");
+    assert_eq!(master.read_text(Duration::from_millis(10)).unwrap_err().kind(),
+               ErrorKind::TimedOut);
+}
+
+#[test]
+fn it_split_keeps_state() {
+    let (master, slave) = openpty().unwrap();
+
+    unsafe { libc::write(slave.as_raw_fd(), b"a\xc3".as_ptr() as *const _, 2) };
+    assert_eq!(master.read_text(Duration::from_secs(5)).unwrap(), "a");
+    master.close_write().unwrap();
+
+    let (reader, mut writer) = master.split().unwrap();
+
+    assert_eq!(writer.write(b"more").unwrap_err().kind(), ErrorKind::BrokenPipe);
+    unsafe { libc::write(slave.as_raw_fd(), b"\xa9".as_ptr() as *const _, 1) };
+    assert_eq!(reader.read_text(Duration::from_secs(5)).unwrap(), "\u{e9}");
+}
+
+#[test]
+fn it_read_until_keeps_rest() {
+    let (master, slave) = openpty().unwrap();
+    let mut buf = [0u8; 64];
+
+    unsafe { libc::write(slave.as_raw_fd(), b"one$ two".as_ptr() as *const _, 8) };
+    assert_eq!(master.read_until(b"$ ", Duration::from_secs(5)).unwrap(), b"one$ ");
+    assert_eq!(master.read_timeout(&mut buf, Duration::from_secs(5)).unwrap(), Some(3));
+    assert_eq!(&buf[..3], b"two");
+}
+
+#[test]
+fn it_master_error() {
+    let path = CString::new("/nonexistent/ptmx").unwrap();
+
+    match Master::new(path.as_ptr()) {
+        Err(MasterError::OpenPtFail(err)) => assert_eq!(err.kind(), ErrorKind::NotFound),
+        other => panic!("{:?}", other),
+    }
+}
+
+#[test]
+fn it_chown_slave() {
+    let (master, _slave) = openpty().unwrap();
+    // Only root can give the slave away, anyone else keeps it.
+    let (uid, gid) = match unsafe { libc::getuid() } {
+        0 => (1, 1),
+        uid => (uid, unsafe { libc::getgid() }),
+    };
+
+    master.chown_slave(uid, gid).unwrap();
+
+    let metadata = fs::metadata(master.pts_name().unwrap()).unwrap();
+
+    assert_eq!((metadata.uid(), metadata.gid()), (uid, gid));
+}
+
+#[test]
+fn it_default_ptmx() {
+    let path = env::var("PTY_PTMX_PATH").unwrap_or_else(|_| "/dev/ptmx".to_string());
+
+    assert_eq!(default_ptmx(), path);
+}
+
+#[test]
+fn it_try_clone() {
+    let (mut master, slave) = openpty().unwrap();
+    let clone = master.try_clone().unwrap();
+    let mut buf = [0u8; 64];
+
+    assert!(clone.as_raw_fd() != master.as_raw_fd());
+    assert!(unsafe { libc::fcntl(clone.as_raw_fd(), libc::F_GETFD) } & libc::FD_CLOEXEC != 0);
+
+    unsafe { libc::write(slave.as_raw_fd(), b"cloned".as_ptr() as *const _, 6) };
+    assert_eq!(clone.read_timeout(&mut buf, Duration::from_secs(5)).unwrap(), Some(6));
+    drop(clone);
+
+    master.write_all(b"original\n").unwrap();
+
+    let len = unsafe { libc::read(slave.as_raw_fd(), buf.as_mut_ptr() as *mut _, buf.len()) };
+
+    assert_eq!(&buf[..len as usize], b"original\n");
+}
+
+#[test]
+fn it_slave_name() {
+    let (master, slave) = openpty().unwrap();
+    let path = PathBuf::from(master.pts_name().unwrap());
+
+    assert_eq!(slave.name().unwrap(), path);
+
+    let wrapped = unsafe { Slave::from_raw_fd(libc::dup(slave.as_raw_fd())) };
+
+    assert_eq!(wrapped.name().unwrap(), path);
+}
+
+#[test]
+fn it_wait_readable_writable() {
+    let (master, slave) = openpty().unwrap();
+
+    assert!(!master.wait_readable(Duration::from_millis(10)).unwrap());
+    assert!(master.wait_writable(Duration::from_secs(5)).unwrap());
+
+    unsafe { libc::write(slave.as_raw_fd(), b"ready".as_ptr() as *const _, 5) };
+    assert!(master.wait_readable(Duration::from_secs(5)).unwrap());
+
+    drop(slave);
+    assert_eq!(master.wait_writable(Duration::from_secs(5)).unwrap_err().kind(),
+               ErrorKind::BrokenPipe);
+}
+
+#[test]
+fn it_peek() {
+    let (mut master, slave) = openpty().unwrap();
+    let mut buf = [0u8; 64];
+
+    unsafe { libc::write(slave.as_raw_fd(), b"banner".as_ptr() as *const _, 6) };
+
+    assert_eq!(master.peek(&mut buf[..3]).unwrap(), 3);
+    assert_eq!(&buf[..3], b"ban");
+    assert_eq!(master.peek(&mut buf).unwrap(), 6);
+    assert_eq!(master.bytes_available().unwrap(), 6);
+    assert_eq!(master.read_timeout(&mut buf[..4], Duration::from_millis(10)).unwrap(), Some(4));
+    assert_eq!(&buf[..4], b"bann");
+    assert_eq!(master.read(&mut buf).unwrap(), 2);
+    assert_eq!(&buf[..2], b"er");
+}
+
+#[test]
+fn it_open_slave() {
+    let (master, slave) = openpty().unwrap();
+    let extra = master.open_slave().unwrap();
+    let mut buf = [0u8; 64];
+
+    assert!(extra.as_raw_fd() != slave.as_raw_fd());
+    assert_eq!(extra.name().unwrap(), slave.name().unwrap());
+    assert!(unsafe { libc::fcntl(extra.as_raw_fd(), libc::F_GETFD) } & libc::FD_CLOEXEC != 0);
+
+    unsafe { libc::write(extra.as_raw_fd(), b"extra".as_ptr() as *const _, 5) };
+    assert_eq!(master.read_timeout(&mut buf, Duration::from_secs(5)).unwrap(), Some(5));
+    assert_eq!(&buf[..5], b"extra");
+}
+
+#[test]
+fn it_byte_counters() {
+    let (mut master, slave) = openpty().unwrap();
+    let mut buf = [0u8; 64];
+
+    master.set_echo(false).unwrap();
+    master.write_all(b"counted\n").unwrap();
+    unsafe { libc::write(slave.as_raw_fd(), b"back".as_ptr() as *const _, 4) };
+    master.read_exact_timeout(&mut buf[..4], Duration::from_secs(5)).unwrap();
+
+    assert_eq!(&buf[..4], b"back");
+    assert_eq!((master.bytes_read(), master.bytes_written()), (4, 8));
+    assert_eq!(master.try_clone().unwrap().bytes_written(), 0);
+
+    master.reset_counters();
+    assert_eq!((master.bytes_read(), master.bytes_written()), (0, 0));
+}
+
+#[cfg(not(any(target_os = "solaris", target_os = "illumos")))]
+#[test]
+fn it_set_async() {
+    let (master, _slave) = openpty().unwrap();
+    let pid = unsafe { libc::getpid() };
+
+    // Nothing is written to the slave, so no `SIGIO` is raised.
+    master.set_async(true, pid).unwrap();
+    assert!(unsafe { libc::fcntl(master.as_raw_fd(), libc::F_GETFL) } & libc::O_ASYNC != 0);
+    assert_eq!(unsafe { libc::fcntl(master.as_raw_fd(), libc::F_GETOWN) }, pid);
+
+    master.set_async(false, pid).unwrap();
+    assert!(unsafe { libc::fcntl(master.as_raw_fd(), libc::F_GETFL) } & libc::O_ASYNC == 0);
+}
+
+#[test]
+fn it_lines_keep_buffered() {
+    let (master, slave) = openpty().unwrap();
+
+    unsafe { libc::write(slave.as_raw_fd(), b"a\nb\n".as_ptr() as *const _, 4) };
+
+    assert_eq!(master.lines().next().unwrap().unwrap(), "a");
+    assert_eq!(master.bytes_available().unwrap(), 3);
+    assert_eq!(master.lines().next().unwrap().unwrap(), "b");
+}
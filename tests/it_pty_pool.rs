@@ -0,0 +1,66 @@
+extern crate pty;
+extern crate libc;
+
+use self::pty::fork::PtyPool;
+
+use std::io::Read;
+use std::os::unix::process::CommandExt;
+use std::process::Command;
+
+#[test]
+fn it_pty_pool() {
+    let pool = PtyPool::new(1).unwrap();
+    let mut ttys = Vec::new();
+
+    for _ in 0..2 {
+        let mut pty = pool.acquire().unwrap();
+        let slave = pty.take_slave().unwrap();
+        let mut command = Command::new("tty");
+        let mut output = String::new();
+
+        unsafe { command.pre_exec(move || slave.make_controlling_terminal()) };
+
+        let mut child = command.spawn().unwrap();
+
+        // The child holds the last slave, so the master reads the end of file.
+        drop(command);
+        assert!(pty.slave().is_none());
+        pty.master().read_to_string(&mut output).unwrap();
+        child.wait().unwrap();
+        ttys.push(output);
+        assert_eq!(pool.available(), 0);
+    }
+
+    assert_eq!(ttys[0], ttys[1]);
+    assert_eq!(pool.available(), 1);
+}
+
+#[test]
+fn it_pty_pool_reset_window_size() {
+    let pool = PtyPool::new(1).unwrap();
+    let size = pool.acquire().unwrap().master().window_size().unwrap();
+
+    pool.acquire().unwrap().master().set_window_size(12, 34).unwrap();
+
+    assert_eq!(pool.acquire().unwrap().master().window_size().unwrap(), size);
+}
+
+#[test]
+fn it_pty_pool_held_slave() {
+    let pool = PtyPool::new(1).unwrap();
+    let mut pty = pool.acquire().unwrap();
+    let slave = pty.take_slave().unwrap();
+    let mut command = Command::new("sh");
+
+    command.args(["-c", "echo ready; exec sleep 10"]);
+    unsafe { command.pre_exec(move || slave.make_controlling_terminal()) };
+
+    let mut child = command.spawn().unwrap();
+
+    pty.master().lines().next().unwrap().unwrap();
+    drop(pty);
+    assert_eq!(pool.available(), 0);
+
+    child.kill().unwrap();
+    child.wait().unwrap();
+}
@@ -0,0 +1,67 @@
+extern crate pty;
+extern crate libc;
+
+use self::pty::prelude::*;
+
+use std::fs::File;
+use std::io::prelude::*;
+use std::os::unix::io::{AsRawFd, FromRawFd};
+use std::thread;
+
+#[test]
+fn it_pump() {
+    let fork = Fork::spawn("sh", &["-c", "echo ready; read x; echo got $x; read y"], &[]).unwrap();
+    let master = fork.is_parent().unwrap();
+    let (input_reader, mut input_writer) = pipe();
+    let (mut output_reader, output_writer) = pipe();
+    let pumping = thread::spawn(move || {
+        pump(&master, input_reader.as_raw_fd(), output_writer.as_raw_fd())
+    });
+
+    // Waits for the child, the input sent before it's set up is lost.
+    read_until(&mut output_reader, "ready");
+    input_writer.write_all(b"pumped\n").unwrap();
+    read_until(&mut output_reader, "got pumped");
+    input_writer.write_all(b"\n").unwrap();
+
+    pumping.join().unwrap().unwrap();
+    fork.wait().unwrap();
+}
+
+#[test]
+fn it_pump_input_end() {
+    let fork = Fork::spawn("sh", &["-c", "stty -echo; echo ready; cat"], &[]).unwrap();
+    let master = fork.is_parent().unwrap();
+    let (input_reader, mut input_writer) = pipe();
+    let (mut output_reader, output_writer) = pipe();
+    let pumping = thread::spawn(move || {
+        pump(&master, input_reader.as_raw_fd(), output_writer.as_raw_fd())
+    });
+
+    read_until(&mut output_reader, "ready");
+    input_writer.write_all(b"line\npartial").unwrap();
+    drop(input_writer);
+    read_until(&mut output_reader, "partial");
+
+    pumping.join().unwrap().unwrap();
+    fork.wait().unwrap();
+}
+
+fn pipe() -> (File, File) {
+    let mut fds = [0; 2];
+
+    assert_eq!(unsafe { libc::pipe(fds.as_mut_ptr()) }, 0);
+    unsafe { (File::from_raw_fd(fds[0]), File::from_raw_fd(fds[1])) }
+}
+
+fn read_until(reader: &mut File, needle: &str) {
+    let mut output = String::new();
+    let mut buf = [0; 64];
+
+    while !output.contains(needle) {
+        let len = reader.read(&mut buf).unwrap();
+
+        assert!(len > 0);
+        output.push_str(&String::from_utf8_lossy(&buf[..len]));
+    }
+}
@@ -0,0 +1,64 @@
+extern crate pty;
+extern crate libc;
+
+use self::pty::prelude::*;
+
+use std::io::prelude::*;
+
+#[test]
+fn it_session() {
+    let mut session = Session::spawn("sh", &["-c", "read x; echo got $x"]).unwrap();
+
+    assert!(session.pid() > 0);
+    assert!(session.pts_name().starts_with("/dev/"));
+    assert_eq!(session.try_wait().unwrap(), None);
+
+    session.resize(30, 100).unwrap();
+    assert_eq!(session.window_size(), (30, 100));
+    assert_eq!(session.master().window_size().unwrap(), (30, 100));
+
+    session.write_all(b"input\n").unwrap();
+
+    let mut string = String::new();
+
+    session.read_to_string(&mut string).unwrap_or_else(|e| panic!("{}", e));
+
+    assert!(string.contains("got input"));
+    assert_eq!(session.wait().unwrap(), WaitStatus::Exited(0));
+    assert_eq!(session.wait().unwrap(), WaitStatus::Exited(0));
+}
+
+#[test]
+fn it_session_kill() {
+    let mut session = Session::spawn("sh", &["-c", "read x"]).unwrap();
+
+    session.kill(libc::SIGTERM).unwrap();
+    assert_eq!(session.wait().unwrap(), WaitStatus::Signaled(libc::SIGTERM));
+}
+
+#[test]
+fn it_session_drop() {
+    let pid = {
+        let session = Session::spawn("sh", &["-c", "read x"]).unwrap();
+
+        session.pid()
+    };
+
+    // The child has been reaped, so its pid is gone.
+    assert_eq!(unsafe { libc::kill(pid, 0) }, -1);
+}
+
+#[test]
+fn it_session_is_child() {
+    match Fork::from_ptmx().unwrap() {
+        fork @ Fork::Parent(..) => {
+            assert!(Session::new(fork).is_ok());
+        }
+        fork @ Fork::Child(..) => {
+            if let Err(ForkError::IsChild) = Session::new(fork) {
+                unsafe { libc::_exit(0) };
+            }
+            unsafe { libc::_exit(1) };
+        }
+    }
+}
@@ -0,0 +1,126 @@
+extern crate pty;
+extern crate libc;
+
+use self::pty::prelude::*;
+
+use std::io;
+use std::io::prelude::*;
+use std::ptr;
+use std::string::String;
+use std::thread;
+
+#[test]
+fn it_spawn() {
+    let fork = Fork::spawn("sh", &["-c", "echo $PTY_SPAWN"], &[("PTY_SPAWN", "spawned!")]).unwrap();
+    let mut master = fork.is_parent().unwrap();
+    let mut string = String::new();
+
+    master.read_to_string(&mut string).unwrap_or_else(|e| panic!("{}", e));
+
+    assert!(string.contains("spawned!"));
+    assert_eq!(fork.wait_status().unwrap(), WaitStatus::Exited(0));
+}
+
+#[test]
+fn it_spawn_with_controlling_terminal() {
+    let fork = Fork::spawn("sh", &["-c", "echo ctty! > /dev/tty"], &[]).unwrap();
+    let mut master = fork.is_parent().unwrap();
+    let mut string = String::new();
+
+    master.read_to_string(&mut string).unwrap_or_else(|e| panic!("{}", e));
+
+    assert!(string.contains("ctty!"));
+    assert_eq!(fork.wait_status().unwrap(), WaitStatus::Exited(0));
+}
+
+#[test]
+fn it_lines() {
+    // The child waits for a line so the output isn't lost when it exits.
+    let fork = Fork::spawn("sh", &["-c", "echo first; echo second; read x"], &[]).unwrap();
+    let mut master = fork.is_parent().unwrap();
+    let lines: Vec<String> = master.lines().take(2).map(|line| line.unwrap()).collect();
+
+    assert_eq!(lines, vec!["first", "second"]);
+    master.write_all(b"\n").unwrap();
+    fork.wait().unwrap();
+}
+
+#[test]
+fn it_release_controlling_terminal() {
+    let fork = Fork::from_ptmx().unwrap();
+
+    if fork.is_parent().is_ok() {
+        assert_eq!(fork.wait_status().unwrap(), WaitStatus::Exited(0));
+    } else {
+        unsafe {
+            // The child leads its session, so it hangs up its own process group.
+            libc::signal(libc::SIGHUP, libc::SIG_IGN);
+
+            let released = release_controlling_terminal().is_ok() &&
+                           release_controlling_terminal().is_ok();
+            let tty = libc::open(b"/dev/tty\0".as_ptr() as *const _, libc::O_RDWR);
+
+            libc::_exit(if released && tty == -1 { 0 } else { 1 });
+        }
+    }
+}
+
+#[test]
+fn it_spawn_detached() {
+    let detached = Fork::spawn_detached("sh", &["-c", "echo $$; read x"], &[]).unwrap();
+    let mut lines = detached.master().lines();
+
+    assert_eq!(lines.next().unwrap().unwrap(), detached.pid().to_string());
+
+    // The session isn't a child, so it can't be waited for.
+    assert_eq!(unsafe { libc::waitpid(detached.pid(), ptr::null_mut(), libc::WNOHANG) }, -1);
+    assert_eq!(io::Error::last_os_error().raw_os_error(), Some(libc::ECHILD));
+
+    detached.kill(libc::SIGKILL).unwrap();
+}
+
+#[test]
+fn it_spawn_detached_exec_fail() {
+    match Fork::spawn_detached("/nonexistent", &[], &[]) {
+        Err(ForkError::ExecFail(err)) => assert_eq!(err.raw_os_error(), Some(libc::ENOENT)),
+        other => panic!("{:?}", other),
+    }
+}
+
+#[test]
+fn it_read_thread() {
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    assert_send_sync::<Fork>();
+    assert_send_sync::<Master>();
+    assert_send_sync::<Slave>();
+
+    let fork = Fork::spawn("sh", &["-c", "echo threaded; read x"], &[]).unwrap();
+    let master = fork.is_parent().unwrap();
+    let reader = thread::spawn(move || master.lines().next().unwrap().unwrap());
+
+    assert_eq!(reader.join().unwrap(), "threaded");
+    fork.parent().unwrap().1.send_eof().unwrap();
+    fork.wait().unwrap();
+}
+
+#[test]
+fn it_spawn_exec_fail() {
+    match Fork::spawn("/nonexistent/command", &[], &[]) {
+        Err(ForkError::ExecFail(err)) => assert_eq!(err.kind(), io::ErrorKind::NotFound),
+        other => panic!("{:?}", other),
+    }
+}
+
+#[test]
+fn it_child_cwd() {
+    let fork = Fork::spawn("sh", &["-c", "cd /; echo ready; read x"], &[]).unwrap();
+    let mut master = fork.is_parent().unwrap();
+
+    assert_eq!(master.lines().next().unwrap().unwrap(), "ready");
+    assert_eq!(fork.child_cwd().unwrap(), std::path::Path::new("/"));
+
+    master.write_all(b"\n").unwrap();
+    assert_eq!(fork.wait_status().unwrap(), WaitStatus::Exited(0));
+    assert_eq!(fork.child_cwd().unwrap_err().kind(), io::ErrorKind::NotFound);
+}
@@ -0,0 +1,85 @@
+extern crate pty;
+
+use self::pty::prelude::*;
+
+use std::io::prelude::*;
+use std::io::ErrorKind;
+use std::string::String;
+use std::thread;
+
+#[test]
+fn it_split() {
+    let fork = Fork::spawn("sh", &[], &[]).unwrap();
+    let (mut reader, mut writer) = fork.is_parent().unwrap().split().unwrap();
+
+    let output = thread::spawn(move || {
+        let mut string = String::new();
+
+        reader.read_to_string(&mut string).unwrap_or_else(|e| panic!("{}", e));
+        string
+    });
+
+    writer.write_all(b"echo split!\nexit\n").unwrap();
+    drop(writer);
+    fork.wait().unwrap();
+
+    assert!(output.join().unwrap().contains("split!"));
+}
+
+#[test]
+fn it_buffered() {
+    let fork = Fork::spawn("sh", &["-c", "echo ready; read x; echo got $x; read y"], &[]).unwrap();
+    let mut master = fork.is_parent().unwrap().buffered();
+    let mut line = String::new();
+
+    master.read_line(&mut line).unwrap();
+    assert_eq!(line, "ready\r\n");
+
+    master.write_all(b"buffered\n").unwrap();
+    while !line.contains("got buffered") {
+        assert!(!master.fill_buf().unwrap().is_empty());
+
+        line.clear();
+        master.read_line(&mut line).unwrap();
+    }
+
+    master.write_all(b"\n").unwrap();
+    fork.wait().unwrap();
+}
+
+#[test]
+fn it_with_capacity() {
+    let fork = Fork::spawn("sh", &["-c", "echo ready; read x"], &[]).unwrap();
+    let mut master = fork.is_parent().unwrap().with_capacity(64 * 1024);
+    let mut line = String::new();
+
+    assert_eq!(master.capacity(), 64 * 1024);
+    assert_eq!(fork.is_parent().unwrap().buffered().capacity(), 8 * 1024);
+
+    master.read_line(&mut line).unwrap();
+    assert_eq!(line, "ready\r\n");
+
+    master.write_all(b"\n").unwrap();
+    fork.wait().unwrap();
+}
+
+#[test]
+fn it_close_write() {
+    let fork = Fork::spawn("sh", &["-c", "echo ready; exec cat"], &[]).unwrap();
+    let (mut reader, mut writer) = fork.is_parent().unwrap().split().unwrap();
+    let mut buf = [0; 64];
+
+    // Waits for the child, the input sent before it's set up is lost.
+    assert!(reader.read(&mut buf).unwrap() > 0);
+    writer.write_all(b"half\n").unwrap();
+    writer.close_write().unwrap();
+
+    assert_eq!(writer.write(b"more\n").unwrap_err().kind(), ErrorKind::BrokenPipe);
+
+    let mut string = String::new();
+
+    reader.read_to_string(&mut string).unwrap();
+    fork.wait().unwrap();
+
+    assert!(string.contains("half"));
+}
@@ -0,0 +1,67 @@
+extern crate pty;
+
+use self::pty::prelude::*;
+
+use std::env;
+use std::fs::{self, File};
+use std::io::prelude::*;
+use std::process;
+
+#[test]
+fn it_tee() {
+    let dir = env::temp_dir().join(format!("pty-tee-{}", process::id()));
+
+    fs::create_dir_all(&dir).unwrap();
+
+    let fork = Fork::spawn("sh", &["-c", "echo ready; read x; echo got $x"], &[]).unwrap();
+    let master = fork.is_parent().unwrap();
+    let log = File::create(dir.join("log")).unwrap();
+    let timing = File::create(dir.join("timing")).unwrap();
+    let mut tee = master.tee_with_timing(log, timing).unwrap();
+    let mut buf = [0; 64];
+    let mut output = Vec::new();
+
+    tee.log_input(File::create(dir.join("input")).unwrap());
+
+    // Waits for the child, the input sent before it's set up is lost.
+    let len = tee.read(&mut buf).unwrap();
+
+    output.extend_from_slice(&buf[..len]);
+    tee.write_all(b"tee\n").unwrap();
+    tee.read_to_end(&mut output).unwrap_or_else(|e| panic!("{}", e));
+    tee.flush().unwrap();
+    fork.wait().unwrap();
+
+    let log = fs::read(dir.join("log")).unwrap();
+    let timing = fs::read_to_string(dir.join("timing")).unwrap();
+    let total: usize = timing.lines()
+                             .map(|line| line.split(' ').nth(1).unwrap().parse::<usize>().unwrap())
+                             .sum();
+
+    assert!(String::from_utf8_lossy(&output).contains("got tee"));
+    assert_eq!(&log[..15], b"Script started\n");
+    assert_eq!(&log[15..], &output[..]);
+    assert_eq!(total, output.len());
+    assert_eq!(fs::read(dir.join("input")).unwrap(), b"tee\n");
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn it_tee_without_timing() {
+    let dir = env::temp_dir().join(format!("pty-tee-plain-{}", process::id()));
+
+    fs::create_dir_all(&dir).unwrap();
+
+    let fork = Fork::spawn("echo", &["plain"], &[]).unwrap();
+    let mut tee = fork.is_parent().unwrap().tee(File::create(dir.join("log")).unwrap());
+    let mut output = String::new();
+
+    tee.read_to_string(&mut output).unwrap_or_else(|e| panic!("{}", e));
+    fork.wait().unwrap();
+
+    assert_eq!(fs::read_to_string(dir.join("log")).unwrap(), output);
+    assert!(output.contains("plain"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
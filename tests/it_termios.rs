@@ -0,0 +1,202 @@
+extern crate pty;
+extern crate libc;
+
+use self::pty::prelude::*;
+use self::pty::fork::Baud;
+
+use std::os::unix::io::AsRawFd;
+use std::time::Duration;
+
+#[test]
+fn it_set_raw() {
+    let fork = Fork::from_ptmx().unwrap();
+
+    if let Ok(master) = fork.is_parent() {
+        let original = master.set_raw().unwrap();
+        let raw = master.set_raw().unwrap();
+
+        assert!(original.c_lflag & libc::ICANON != 0);
+        assert!(raw.c_lflag & (libc::ICANON | libc::ECHO) == 0);
+
+        master.set_termios(&original).unwrap();
+    } else {
+        unsafe { libc::_exit(0) };
+    }
+}
+
+#[test]
+fn it_send_eof() {
+    let fork = Fork::spawn("sh", &["-c", "echo ready; exec cat"], &[]).unwrap();
+    let master = fork.is_parent().unwrap();
+
+    master.lines().next().unwrap().unwrap();
+
+    master.send_eof().unwrap();
+
+    assert_eq!(fork.wait_status().unwrap(), WaitStatus::Exited(0));
+}
+
+#[test]
+fn it_send_signal_char() {
+    let fork = Fork::spawn("sh", &["-c", "echo ready; exec cat"], &[]).unwrap();
+    let master = fork.is_parent().unwrap();
+
+    master.lines().next().unwrap().unwrap();
+
+    master.send_signal_char(libc::SIGINT).unwrap();
+
+    assert_eq!(fork.wait_status().unwrap(), WaitStatus::Signaled(libc::SIGINT));
+}
+
+#[test]
+fn it_set_echo() {
+    let (master, _slave) = openpty().unwrap();
+
+    master.set_echo(false).unwrap();
+    assert!(master.set_raw().unwrap().c_lflag & libc::ECHO == 0);
+}
+
+#[test]
+fn it_termios() {
+    let (master, _slave) = openpty().unwrap();
+    let termios = master.termios().unwrap();
+
+    assert!(termios.echo() && termios.icanon() && termios.isig());
+
+    master.apply(&termios.with_echo(false).with_icanon(false)).unwrap();
+
+    let changed = master.termios().unwrap();
+
+    assert!(!changed.echo() && !changed.icanon() && changed.isig());
+    assert_eq!(changed.as_raw().c_lflag, termios.as_raw().c_lflag & !(libc::ECHO | libc::ICANON));
+}
+
+#[test]
+fn it_set_speed() {
+    let (master, slave) = openpty().unwrap();
+
+    master.set_speed(Baud::B9600).unwrap();
+    assert_eq!(master.speed().unwrap(), Some(Baud::B9600));
+
+    let mut termios: libc::termios = unsafe { std::mem::zeroed() };
+
+    unsafe { libc::tcgetattr(slave.as_raw_fd(), &mut termios) };
+    assert_eq!(unsafe { libc::cfgetispeed(&termios) }, libc::B9600);
+}
+
+#[test]
+fn it_foreground_pgrp() {
+    let fork = Fork::spawn("sh", &["-c", "echo ready; read x"], &[]).unwrap();
+    let (pid, master) = fork.parent().unwrap();
+
+    master.read_until(b"ready", Duration::from_secs(5)).unwrap();
+    assert_eq!(master.foreground_pgrp().unwrap(), pid);
+
+    master.send_eof().unwrap();
+    fork.wait().unwrap();
+}
+
+#[test]
+fn it_set_noncanonical() {
+    let (master, _slave) = openpty().unwrap();
+
+    master.set_noncanonical(1, 0).unwrap();
+    master.set_cc(libc::VINTR, 0x07).unwrap();
+
+    let termios = master.termios().unwrap();
+
+    assert!(!termios.icanon() && termios.echo());
+    assert_eq!(termios.as_raw().c_cc[libc::VMIN], 1);
+    assert_eq!(termios.as_raw().c_cc[libc::VTIME], 0);
+    assert_eq!(termios.as_raw().c_cc[libc::VINTR], 0x07);
+    assert_eq!(master.set_cc(libc::NCCS, 0).unwrap_err().kind(), std::io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn it_send_control() {
+    let (master, slave) = openpty().unwrap();
+    let mut buf = [0u8; 8];
+
+    master.set_raw().unwrap();
+    for &c in &['c', 'Z', '?', '@'] {
+        master.send_control(c).unwrap();
+    }
+
+    let mut len = 0;
+
+    // The bytes reach the slave asynchronously.
+    while len < 4 {
+        len += unsafe { libc::read(slave.as_raw_fd(), buf[len..].as_mut_ptr() as *mut _, 4 - len) }
+            as usize;
+    }
+    assert_eq!(&buf[..len], b"\x03\x1a\x7f\x00");
+    assert_eq!(master.send_control('1').unwrap_err().kind(), std::io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn it_reset() {
+    let (master, _slave) = openpty().unwrap();
+
+    master.set_raw().unwrap();
+    master.reset().unwrap();
+
+    let termios = master.termios().unwrap();
+    let raw = termios.as_raw();
+
+    assert!(termios.echo() && termios.icanon() && termios.isig());
+    assert!(raw.c_oflag & libc::ONLCR != 0 && raw.c_iflag & libc::ICRNL != 0);
+    assert_eq!((raw.c_cc[libc::VINTR], raw.c_cc[libc::VEOF]), (0x03, 0x04));
+}
+
+#[test]
+fn it_set_output_processing() {
+    let (master, slave) = openpty().unwrap();
+    let mut buf = [0; 16];
+
+    assert!(master.output_processing().unwrap());
+    master.set_output_processing(false).unwrap();
+    assert!(!master.output_processing().unwrap());
+
+    unsafe { libc::write(slave.as_raw_fd(), b"a\nb\n".as_ptr() as *const libc::c_void, 4) };
+
+    let len = master.read_timeout(&mut buf, Duration::from_secs(5)).unwrap().unwrap();
+
+    assert_eq!(&buf[..len], b"a\nb\n");
+
+    master.set_output_processing(true).unwrap();
+    assert!(master.output_processing().unwrap());
+}
+
+#[cfg(any(target_os = "linux",
+          target_os = "macos",
+          target_os = "ios",
+          target_os = "freebsd",
+          target_os = "dragonfly"))]
+#[test]
+fn it_set_packet_mode() {
+    use self::pty::fork::PacketControl;
+
+    let (master, slave) = openpty().unwrap();
+    let mut buf = [0; 16];
+
+    master.set_packet_mode(true).unwrap();
+
+    unsafe { libc::write(slave.as_raw_fd(), b"hi".as_ptr() as *const libc::c_void, 2) };
+
+    let len = master.read_timeout(&mut buf, Duration::from_secs(5)).unwrap().unwrap();
+    let (control, data) = PacketControl::parse(&buf[..len]).unwrap();
+
+    assert!(control.is_data());
+    assert_eq!(data, b"hi");
+
+    unsafe { libc::tcflow(slave.as_raw_fd(), libc::TCOOFF) };
+
+    let len = master.read_timeout(&mut buf, Duration::from_secs(5)).unwrap().unwrap();
+    let (control, data) = PacketControl::parse(&buf[..len]).unwrap();
+
+    assert!(control.stop() && !control.is_data());
+    assert!(data.is_empty());
+    assert_eq!(PacketControl::parse(&[]), None);
+
+    master.set_packet_mode(false).unwrap();
+}
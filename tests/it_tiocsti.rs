@@ -0,0 +1,24 @@
+#![cfg(feature = "tiocsti")]
+
+extern crate pty;
+extern crate libc;
+
+use self::pty::prelude::*;
+
+use std::os::unix::io::AsRawFd;
+
+#[test]
+fn it_push_input() {
+    let (_master, slave) = openpty().unwrap();
+    let mut buf = [0u8; 16];
+
+    // Needs `CAP_SYS_ADMIN` since the slave isn't the controlling terminal.
+    if let Err(err) = slave.push_input(b"typed\n") {
+        assert!(err.raw_os_error() == Some(libc::EPERM) || err.raw_os_error() == Some(libc::EIO));
+        return;
+    }
+
+    let len = unsafe { libc::read(slave.as_raw_fd(), buf.as_mut_ptr() as *mut libc::c_void, 16) };
+
+    assert_eq!(&buf[..len as usize], b"typed\n");
+}
@@ -0,0 +1,205 @@
+extern crate pty;
+extern crate libc;
+
+use self::pty::prelude::*;
+
+use std::time::Duration;
+
+#[test]
+fn it_wait_status() {
+    let fork = Fork::from_ptmx().unwrap();
+
+    if fork.is_parent().is_ok() {
+        assert_eq!(fork.wait_status().unwrap(), WaitStatus::Exited(3));
+    } else {
+        unsafe { libc::_exit(3) };
+    }
+}
+
+#[test]
+fn it_try_wait() {
+    let fork = Fork::from_ptmx().unwrap();
+
+    if fork.is_parent().is_ok() {
+        loop {
+            if let Some(_pid) = fork.try_wait().unwrap() {
+                break;
+            }
+        }
+    } else {
+        unsafe { libc::_exit(0) };
+    }
+}
+
+#[test]
+fn it_kill() {
+    let fork = Fork::spawn("sleep", &["10"], &[]).unwrap();
+
+    match fork.kill(-1) {
+        Err(ForkError::KillFail(err)) => assert_eq!(err.raw_os_error(), Some(libc::EINVAL)),
+        other => panic!("{:?}", other),
+    }
+    fork.kill(libc::SIGKILL).unwrap();
+
+    assert_eq!(fork.wait_status().unwrap(), WaitStatus::Signaled(libc::SIGKILL));
+}
+
+#[test]
+fn it_killpg() {
+    let fork = Fork::spawn("sh", &["-c", "echo ready; sleep 10"], &[]).unwrap();
+
+    // Waits until the child leads its process group.
+    fork.is_parent().unwrap().lines().next().unwrap().unwrap();
+    fork.killpg(libc::SIGTERM).unwrap();
+
+    assert_eq!(fork.wait_status().unwrap(), WaitStatus::Signaled(libc::SIGTERM));
+}
+
+#[test]
+fn it_wait_no_child() {
+    let fork = Fork::spawn("true", &[], &[]).unwrap();
+
+    fork.wait().unwrap();
+
+    match fork.wait_status() {
+        Err(err @ ForkError::NoChild) => assert_eq!(err.to_string(), "no such child"),
+        other => panic!("{:?}", other),
+    }
+    match fork.try_wait() {
+        Err(ForkError::NoChild) => {}
+        other => panic!("{:?}", other),
+    }
+}
+
+#[test]
+fn it_is_alive() {
+    let fork = Fork::spawn("sleep", &["10"], &[]).unwrap();
+
+    assert!(fork.is_alive().unwrap());
+
+    fork.kill(libc::SIGKILL).unwrap();
+
+    // The exited child isn't alive before it's waited for, nor reaped.
+    while fork.is_alive().unwrap() {
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    assert_eq!(fork.wait_status().unwrap(), WaitStatus::Signaled(libc::SIGKILL));
+    assert!(!fork.is_alive().unwrap());
+}
+
+#[test]
+fn it_reap_on_drop() {
+    let fork = ReapOnDrop::new(Fork::spawn("sleep", &["10"], &[]).unwrap());
+    let pid = fork.pgid().unwrap();
+    let mut status = 0;
+
+    drop(fork);
+
+    assert_eq!(unsafe { libc::waitpid(pid, &mut status, libc::WNOHANG) }, -1);
+
+    let fork = ReapOnDrop::new(Fork::spawn("true", &[], &[]).unwrap()).into_inner();
+
+    assert_eq!(fork.wait_status().unwrap(), WaitStatus::Exited(0));
+}
+
+#[test]
+fn it_parent() {
+    let fork = Fork::spawn("true", &[], &[]).unwrap();
+    let (pid, master) = fork.parent().unwrap();
+
+    assert_eq!(pid, fork.pgid().unwrap());
+    assert!(master.pts_name().unwrap().starts_with("/dev/"));
+    fork.wait().unwrap();
+}
+
+#[cfg(any(target_os = "linux",
+          target_os = "android",
+          target_os = "macos",
+          target_os = "ios",
+          target_os = "freebsd",
+          target_os = "dragonfly",
+          target_os = "netbsd"))]
+#[test]
+fn it_wait4() {
+    // The child burns some CPU time before exiting.
+    let fork = Fork::spawn("sh", &["-c", "i=0; while [ $i -lt 20000 ]; do i=$((i+1)); done; exit 4"],
+                           &[])
+        .unwrap();
+    let (status, usage) = fork.wait4().unwrap();
+
+    assert_eq!(status, WaitStatus::Exited(4));
+    assert!(usage.ru_utime.tv_sec > 0 || usage.ru_utime.tv_usec > 0);
+    assert!(usage.ru_maxrss > 0);
+}
+
+#[test]
+fn it_suspend_resume() {
+    let fork = Fork::spawn("sleep", &["10"], &[]).unwrap();
+    let pid = fork.pgid().unwrap();
+    let mut status = 0;
+
+    fork.suspend().unwrap();
+    assert_eq!(unsafe { libc::waitpid(pid, &mut status, libc::WUNTRACED) }, pid);
+    assert!(libc::WIFSTOPPED(status));
+
+    fork.resume().unwrap();
+    assert_eq!(unsafe { libc::waitpid(pid, &mut status, libc::WCONTINUED) }, pid);
+    assert!(libc::WIFCONTINUED(status));
+
+    fork.kill(libc::SIGKILL).unwrap();
+    fork.wait().unwrap();
+}
+
+#[test]
+fn it_wait_for_state() {
+    let fork = Fork::spawn("sleep", &["10"], &[]).unwrap();
+
+    fork.suspend().unwrap();
+    assert_eq!(fork.wait_for_state().unwrap(), WaitStatus::Stopped(libc::SIGSTOP));
+
+    fork.resume().unwrap();
+    assert_eq!(fork.wait_for_state().unwrap(), WaitStatus::Continued);
+
+    fork.kill(libc::SIGKILL).unwrap();
+    assert_eq!(fork.wait_for_state().unwrap(), WaitStatus::Signaled(libc::SIGKILL));
+}
+
+#[test]
+fn it_terminate() {
+    let fork = Fork::spawn("sleep", &["10"], &[]).unwrap();
+
+    assert_eq!(fork.terminate(Duration::from_secs(5)).unwrap(),
+               WaitStatus::Signaled(libc::SIGTERM));
+
+    let fork = Fork::spawn("sh", &["-c", "trap '' TERM; echo ready; exec sleep 10"], &[]).unwrap();
+    let (_, master) = fork.parent().unwrap();
+
+    master.read_until(b"ready", Duration::from_secs(5)).unwrap();
+    assert_eq!(fork.terminate(Duration::from_millis(200)).unwrap(),
+               WaitStatus::Signaled(libc::SIGKILL));
+}
+
+extern "C" fn ignore_signal(_: libc::c_int) {}
+
+#[test]
+fn it_wait_status_interrupted() {
+    // The handler is installed without `SA_RESTART`, so the signal
+    // interrupts the wait with `EINTR`.
+    unsafe {
+        let mut action: libc::sigaction = std::mem::zeroed();
+
+        action.sa_sigaction = ignore_signal as *const () as libc::sighandler_t;
+        libc::sigemptyset(&mut action.sa_mask);
+        assert_eq!(libc::sigaction(libc::SIGUSR2, &action, std::ptr::null_mut()), 0);
+    }
+
+    let fork = Fork::spawn("sleep", &["1"], &[]).unwrap();
+    let waiter = unsafe { libc::pthread_self() } as usize;
+    let interrupter = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(200));
+        unsafe { libc::pthread_kill(waiter as libc::pthread_t, libc::SIGUSR2) };
+    });
+
+    assert_eq!(fork.wait_status().unwrap(), WaitStatus::Exited(0));
+    interrupter.join().unwrap();
+}
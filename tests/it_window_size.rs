@@ -0,0 +1,27 @@
+extern crate pty;
+extern crate libc;
+
+use self::pty::prelude::*;
+
+#[test]
+fn it_window_size() {
+    let fork = Fork::from_ptmx().unwrap();
+
+    if let Ok(master) = fork.is_parent() {
+        master.set_window_size(40, 120).unwrap();
+
+        assert_eq!(master.window_size().unwrap(), (40, 120));
+    } else {
+        unsafe { libc::_exit(0) };
+    }
+}
+
+#[test]
+fn it_slave_window_size() {
+    let (master, slave) = openpty().unwrap();
+
+    slave.set_window_size(24, 81).unwrap();
+
+    assert_eq!(slave.window_size().unwrap(), (24, 81));
+    assert_eq!(master.window_size().unwrap(), (24, 81));
+}
@@ -1,2 +1,18 @@
 mod it_fork_with_new_pty;
 mod it_can_read_write;
+mod it_window_size;
+mod it_wait_status;
+mod it_termios;
+mod it_spawn;
+mod it_split;
+mod it_nonblocking;
+mod it_openpty;
+mod it_fork_builder;
+mod it_async_master;
+mod it_mio_source;
+mod it_exec;
+mod it_pump;
+mod it_pty_pool;
+mod it_session;
+mod it_tiocsti;
+mod it_tee;